@@ -0,0 +1,83 @@
+use advent_of_code::bench::{self, BenchOptions, Stats};
+use advent_of_code::day01::{self, Document};
+use advent_of_code::generate::{Rng, DEFAULT_SEED};
+use advent_of_code::registry;
+
+const LINES: usize = 200_000;
+
+const SPELLED_DIGITS: [&str; 18] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "1", "2", "3", "4",
+    "5", "6", "7", "8", "9",
+];
+
+fn find_and_rfind(document: &Document) -> Option<u64> {
+    document
+        .lines()
+        .iter()
+        .map(|line| {
+            let first = SPELLED_DIGITS
+                .iter()
+                .enumerate()
+                .filter_map(|(digit, pattern)| Some((line.find(pattern)?, digit)))
+                .min()?;
+            let last = SPELLED_DIGITS
+                .iter()
+                .enumerate()
+                .filter_map(|(digit, pattern)| Some((line.rfind(pattern)?, digit)))
+                .max()?;
+            Some((first.1 as u64 % 9 + 1) * 10 + last.1 as u64 % 9 + 1)
+        })
+        .sum()
+}
+
+fn main() {
+    let generate = registry::find(1)
+        .and_then(|puzzle| puzzle.generate)
+        .expect("day 01 has a generator");
+    let input = generate(&mut Rng::new(DEFAULT_SEED), LINES).input;
+    let document = input.parse::<Document>().unwrap();
+    assert_eq!(
+        find_and_rfind(&document),
+        day01::solve_part2(&document).ok()
+    );
+
+    let options = BenchOptions::default();
+    let rows = [
+        (
+            "find/rfind",
+            bench::measure(&options, || find_and_rfind(&document)),
+        ),
+        (
+            "automaton",
+            bench::measure(&options, || day01::solve_part2(&document)),
+        ),
+    ];
+    println!(
+        "day 01 part 2 on {} lines ({:.1} MiB)",
+        LINES,
+        input.len() as f64 / (1024.0 * 1024.0)
+    );
+    println!(
+        "{:<11} {:>12} {:>12} {:>12} {:>12}",
+        "scanner", "min", "median", "mean", "stddev"
+    );
+    for (
+        scanner,
+        Stats {
+            min,
+            median,
+            mean,
+            stddev,
+        },
+    ) in rows
+    {
+        println!(
+            "{:<11} {:>12} {:>12} {:>12} {:>12}",
+            scanner,
+            format!("{:.2?}", min),
+            format!("{:.2?}", median),
+            format!("{:.2?}", mean),
+            format!("{:.2?}", stddev),
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+use std::{env, fs};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let src = Path::new(&manifest_dir).join("src");
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let mut days = fs::read_dir(&src)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let module = name.strip_suffix(".rs")?;
+            let number = module.strip_prefix("day")?;
+            (number.len() == 2 && number.bytes().all(|b| b.is_ascii_digit()))
+                .then(|| (module.to_string(), entry.path()))
+        })
+        .collect::<Vec<_>>();
+    days.sort();
+
+    let modules = days
+        .iter()
+        .map(|(module, path)| {
+            format!(
+                "#[path = {:?}]\npub mod {};\n",
+                path.display().to_string(),
+                module
+            )
+        })
+        .collect::<String>();
+    let puzzles = format!(
+        "&[{}]\n",
+        days.iter()
+            .map(|(module, _)| format!("crate::{}::PUZZLE", module))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("days.rs"), modules).unwrap();
+    fs::write(Path::new(&out_dir).join("puzzles.rs"), puzzles).unwrap();
+
+    // The day template rendered as it would be for a new day, so the tests
+    // can check that a freshly scaffolded day keeps the suite green.
+    let template = fs::read_to_string(src.join("scaffold/template.rs.in")).unwrap();
+    let scaffolded = template
+        .replace("{{day}}", "99")
+        .replace("{{day:02}}", "99")
+        .replace("{{title}}", "Scaffolded");
+    fs::write(Path::new(&out_dir).join("scaffolded.rs"), scaffolded).unwrap();
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+pub const ANSWERS_FILE_VAR: &str = "AOC_ANSWERS_FILE";
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.txt";
+
+const HEADER: &str = "# day part input-id answer
+# Verified answers, keyed by a hash of the puzzle input. Update with `aoc --record`.
+";
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Unreadable { path: PathBuf, source: io::Error },
+    Malformed { path: PathBuf, line: usize },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Unreadable { path, source } => {
+                write!(f, "can't read {}: {}", path.display(), source)
+            }
+            AnswersError::Malformed { path, line } => write!(
+                f,
+                "{}:{}: expected `day part input-id answer`",
+                path.display(),
+                line
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    entries: BTreeMap<(u8, u8, u64), String>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Answers, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Answers::parse(&content).map_err(|line| AnswersError::Malformed {
+                path: path.to_path_buf(),
+                line,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(source) => Err(AnswersError::Unreadable {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Answers, usize> {
+        let mut entries = BTreeMap::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.splitn(4, ' ').collect::<Vec<_>>();
+            let entry = match fields[..] {
+                [day, part, id, answer] => day
+                    .parse()
+                    .ok()
+                    .zip(part.parse().ok())
+                    .zip(u64::from_str_radix(id, 16).ok())
+                    .map(|((day, part), id)| ((day, part, id), answer.to_string())),
+                _ => None,
+            };
+            let (key, answer) = entry.ok_or(number + 1)?;
+            entries.insert(key, answer);
+        }
+        Ok(Answers { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = HEADER.to_string();
+        for ((day, part, id), answer) in &self.entries {
+            writeln!(content, "{:02} {} {:016x} {}", day, part, id, answer).unwrap();
+        }
+        fs::write(path, content)
+    }
+
+    pub fn expected(&self, day: u8, part: u8, input_id: u64) -> Option<&str> {
+        self.entries.get(&(day, part, input_id)).map(String::as_str)
+    }
+
+    /// Keeps an already verified answer that differs, and returns it instead.
+    pub fn record(
+        &mut self,
+        day: u8,
+        part: u8,
+        input_id: u64,
+        answer: String,
+    ) -> Result<bool, String> {
+        match self.entries.get(&(day, part, input_id)) {
+            Some(verified) if *verified == answer => Ok(false),
+            Some(verified) => Err(verified.clone()),
+            None => Ok(self.overwrite(day, part, input_id, answer)),
+        }
+    }
+
+    pub fn overwrite(&mut self, day: u8, part: u8, input_id: u64, answer: String) -> bool {
+        self.entries.insert((day, part, input_id), answer.clone()) != Some(answer)
+    }
+}
+
+pub fn answers_path() -> PathBuf {
+    env::var_os(ANSWERS_FILE_VAR)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ANSWERS_FILE))
+}
+
+pub fn input_id(input: &str) -> u64 {
+    input
+        .trim_end()
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+#[cfg(test)]
+pub(crate) fn assert_solution<S: crate::Solution>(day: u8, part: u8) {
+    let answers = Answers::load(&answers_path()).unwrap();
+    let verified = answers
+        .entries
+        .keys()
+        .any(|&(d, p, _)| (d, p) == (day, part));
+    let input = match crate::input::load_day(day) {
+        Ok(input) => crate::normalize::normalize(&input).into_owned(),
+        Err(e) if verified => panic!("day {} part {} has a verified answer but {}", day, part, e),
+        Err(e) => {
+            eprintln!("skipping day {} part {}: {}", day, part, e);
+            return;
+        }
+    };
+    if input.is_empty() && !verified {
+        eprintln!("skipping day {} part {}: the input is empty", day, part);
+        return;
+    }
+    let expected = answers
+        .expected(day, part, input_id(&input))
+        .unwrap_or_else(|| {
+            panic!(
+                "day {} part {}: no verified answer for this input, run `aoc {} --record`",
+                day, part, day
+            )
+        });
+    let answer = S::parse(&input)
+        .and_then(|parsed| match part {
+            1 => S::part1(&parsed),
+            _ => S::part2(&parsed),
+        })
+        .map(|answer| answer.to_string());
+    assert_eq!(answer.as_deref(), Ok(expected));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_lookup() {
+        let answers = Answers::parse(
+            "# comment
+
+05 1 00000000000000ff 199602917
+05 2 00000000000000ff 2254686
+",
+        )
+        .unwrap();
+        assert_eq!(answers.expected(5, 1, 0xff), Some("199602917"));
+        assert_eq!(answers.expected(5, 2, 0xff), Some("2254686"));
+        assert_eq!(answers.expected(5, 2, 0xfe), None);
+        assert_eq!(Answers::parse("05 1 ff\n"), Err(1));
+        assert_eq!(Answers::parse("\n05 x ff 12\n"), Err(2));
+    }
+
+    #[test]
+    fn record_answers() {
+        let mut answers = Answers::default();
+        assert_eq!(answers.record(1, 1, 42, "54561".to_string()), Ok(true));
+        assert_eq!(answers.record(1, 1, 42, "54561".to_string()), Ok(false));
+        assert_eq!(
+            answers.record(1, 1, 42, "54562".to_string()),
+            Err("54561".to_string())
+        );
+        assert_eq!(answers.expected(1, 1, 42), Some("54561"));
+        assert!(answers.overwrite(1, 1, 42, "54562".to_string()));
+        assert!(!answers.overwrite(1, 1, 42, "54562".to_string()));
+        assert_eq!(answers.expected(1, 1, 42), Some("54562"));
+    }
+
+    #[test]
+    fn input_id_ignores_trailing_whitespace() {
+        assert_eq!(input_id("Time: 7\n"), input_id("Time: 7"));
+        assert_ne!(input_id("Time: 7"), input_id("Time: 8"));
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+
+const MISSING: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Automaton {
+    forward: Dfa,
+    backward: Dfa,
+    lengths: Vec<usize>,
+    longest: usize,
+}
+
+impl Automaton {
+    pub fn new<P: AsRef<[u8]>>(patterns: &[P]) -> Automaton {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| pattern.as_ref())
+            .collect::<Vec<_>>();
+        let lengths = patterns
+            .iter()
+            .map(|pattern| pattern.len())
+            .collect::<Vec<_>>();
+        Automaton {
+            forward: Dfa::new(patterns.iter().map(|pattern| pattern.to_vec())),
+            backward: Dfa::new(
+                patterns
+                    .iter()
+                    .map(|pattern| pattern.iter().rev().copied().collect()),
+            ),
+            longest: lengths.iter().copied().max().unwrap_or(0),
+            lengths,
+        }
+    }
+
+    pub fn first(&self, haystack: &str) -> Option<Match> {
+        let mut state = 0;
+        let mut first: Option<Match> = None;
+        for (i, &byte) in haystack.as_bytes().iter().enumerate() {
+            if first.is_some_and(|first| i >= first.start + self.longest) {
+                break;
+            }
+            state = self.forward.next(state, byte);
+            for &pattern in &self.forward.outputs[state] {
+                let start = i + 1 - self.lengths[pattern];
+                if first.is_none_or(|first| start <= first.start) {
+                    first = Some(Match {
+                        pattern,
+                        start,
+                        end: i + 1,
+                    });
+                }
+            }
+        }
+        first
+    }
+
+    /// Every match, overlapping ones included, by start then longest first.
+    pub fn all(&self, haystack: &str) -> Vec<Match> {
+        let mut state = 0;
+        let mut matches = vec![];
+        for (i, &byte) in haystack.as_bytes().iter().enumerate() {
+            state = self.forward.next(state, byte);
+            matches.extend(self.forward.outputs[state].iter().map(|&pattern| Match {
+                pattern,
+                start: i + 1 - self.lengths[pattern],
+                end: i + 1,
+            }));
+        }
+        matches.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end)));
+        matches
+    }
+
+    pub fn last(&self, haystack: &str) -> Option<Match> {
+        let mut state = 0;
+        for (start, &byte) in haystack.as_bytes().iter().enumerate().rev() {
+            state = self.backward.next(state, byte);
+            if let Some(&pattern) = self.backward.outputs[state].first() {
+                return Some(Match {
+                    pattern,
+                    start,
+                    end: start + self.lengths[pattern],
+                });
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Dfa {
+    transitions: Vec<[u32; 256]>,
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Dfa {
+    fn new(patterns: impl Iterator<Item = Vec<u8>>) -> Dfa {
+        let mut transitions = vec![[MISSING; 256]];
+        let mut outputs = vec![vec![]];
+        for (index, pattern) in patterns.enumerate() {
+            let mut state = 0;
+            for &byte in &pattern {
+                if transitions[state][usize::from(byte)] == MISSING {
+                    transitions[state][usize::from(byte)] = transitions.len() as u32;
+                    transitions.push([MISSING; 256]);
+                    outputs.push(vec![]);
+                }
+                state = transitions[state][usize::from(byte)] as usize;
+            }
+            outputs[state].push(index);
+        }
+
+        let mut failures = vec![0; transitions.len()];
+        let mut queue = VecDeque::new();
+        for next in transitions[0].iter_mut() {
+            if *next == MISSING {
+                *next = 0;
+            } else {
+                queue.push_back(*next as usize);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[failures[state]].clone();
+            outputs[state].extend(inherited);
+            let fallbacks = transitions[failures[state]];
+            for (next, &fallback) in transitions[state].iter_mut().zip(&fallbacks) {
+                match *next {
+                    MISSING => *next = fallback,
+                    child => {
+                        failures[child as usize] = fallback as usize;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+        Dfa {
+            transitions,
+            outputs,
+        }
+    }
+
+    fn next(&self, state: usize, byte: u8) -> usize {
+        self.transitions[state][usize::from(byte)] as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(m: Option<Match>) -> Option<(usize, usize, usize)> {
+        m.map(|m| (m.pattern, m.start, m.end))
+    }
+
+    #[test]
+    fn overlapping_spelled_digits() {
+        let automaton = Automaton::new(&["one", "two", "eight", "2"]);
+        assert_eq!(found(automaton.first("xeightwo")), Some((2, 1, 6)));
+        assert_eq!(found(automaton.last("xeightwo")), Some((1, 5, 8)));
+        assert_eq!(found(automaton.first("twone")), Some((1, 0, 3)));
+        assert_eq!(found(automaton.last("twone2x")), Some((3, 5, 6)));
+        assert_eq!(automaton.first("nothing here"), None);
+        assert_eq!(automaton.last(""), None);
+    }
+
+    #[test]
+    fn nested_patterns() {
+        let automaton = Automaton::new(&["she", "he", "hers", "é"]);
+        assert_eq!(found(automaton.first("ushers")), Some((0, 1, 4)));
+        assert_eq!(found(automaton.last("ushers")), Some((2, 2, 6)));
+        assert_eq!(found(automaton.first("hé she")), Some((3, 1, 3)));
+        assert_eq!(found(automaton.last("she é")), Some((3, 4, 6)));
+        assert_eq!(
+            automaton
+                .all("ushers")
+                .into_iter()
+                .map(|m| found(Some(m)).unwrap())
+                .collect::<Vec<_>>(),
+            [(0, 1, 4), (2, 2, 6), (1, 2, 4)]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::ParseError;
+use crate::normalize;
+use crate::Solution;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub warmups: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            warmups: 3,
+            iterations: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        if n == 0 {
+            return Stats {
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                stddev: Duration::ZERO,
+            };
+        }
+
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayBench {
+    pub day: u8,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Stats)>,
+}
+
+pub fn measure<T>(options: &BenchOptions, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..options.warmups {
+        black_box(f());
+    }
+    let samples = (0..options.iterations)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    Stats::from_samples(&samples)
+}
+
+pub fn bench<S: Solution>(
+    day: u8,
+    input: &str,
+    parts: &[u8],
+    options: &BenchOptions,
+) -> Result<DayBench, ParseError> {
+    let input = normalize::normalize(input);
+    let parsed = S::parse(&input)?;
+    let parse = measure(options, || S::parse(&input));
+
+    let solve = |part| match part {
+        1 => S::part1(&parsed),
+        _ => S::part2(&parsed),
+    };
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            solve(part)?;
+            Ok((part, measure(options, || solve(part))))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(DayBench { day, parse, parts })
+}
+
+pub fn render_table(benches: &[DayBench]) -> String {
+    let mut table = format!(
+        "{:<4} {:<7} {:>12} {:>12} {:>12} {:>12}\n",
+        "day", "step", "min", "median", "mean", "stddev"
+    );
+    for bench in benches {
+        let steps = std::iter::once(("parse".to_string(), &bench.parse)).chain(
+            bench
+                .parts
+                .iter()
+                .map(|(part, stats)| (format!("part {}", part), stats)),
+        );
+        for (step, stats) in steps {
+            writeln!(
+                table,
+                "{:<4} {:<7} {:>12} {:>12} {:>12} {:>12}",
+                format!("{:02}", bench.day),
+                step,
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.mean),
+                format!("{:.2?}", stats.stddev),
+            )
+            .unwrap();
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_from_samples() {
+        let samples = [4, 1, 3, 2]
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect::<Vec<_>>();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean.as_micros(), 2500);
+        assert_eq!(stats.stddev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn table_layout() {
+        let stats = Stats::from_samples(&[Duration::from_millis(1)]);
+        let table = render_table(&[DayBench {
+            day: 5,
+            parse: stats,
+            parts: vec![(2, stats)],
+        }]);
+        assert_eq!(
+            table,
+            "day  step             min       median         mean       stddev
+05   parse         1.00ms       1.00ms       1.00ms       0.00ns
+05   part 2        1.00ms       1.00ms       1.00ms       0.00ns
+"
+        );
+    }
+}
@@ -0,0 +1,952 @@
+use crate::bench::BenchOptions;
+use crate::day01::MissingDigits;
+use crate::differential;
+use crate::generate;
+use crate::input::InputSource;
+use crate::minimize::Predicate;
+use crate::report::Format;
+use std::fmt;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "usage: aoc [--all | DAYS [PART]] [--input PATH | --stdin] [--strict]
+           [--lexicon PATH] [--ignore-case] [--missing-digits POLICY]
+       aoc --list
+       aoc new-day DAY [TITLE] [--root PATH]
+       aoc fetch DAYS [--base-url URL]
+       aoc submit DAY PART [ANSWER] [--base-url URL]
+       aoc generate DAY [--seed N] [--size N]
+       aoc diff [DAYS] [--seed N] [--runs N] [--size N]
+       aoc minimize DAY PREDICATE [--input PATH | --stdin] [--output PATH]
+       aoc audit [--input PATH | --stdin] [--format text | csv] [--lexicon PATH]
+           [--ignore-case]
+
+  DAYS    a day (5), an inclusive range (1..4) or a list (1,3,6)
+  PART    1 or 2, both parts are run when omitted
+
+commands:
+  new-day DAY [TITLE]  create src/dayNN.rs, an empty input and an example stub
+                       under --root (default the current directory)
+  fetch DAYS           download the puzzle inputs that aren't cached yet
+  submit DAY PART [ANSWER]
+                       submit ANSWER, or the computed answer, and remember the reply
+  generate DAY         print a synthetic input for DAY, its answers go to stderr
+  diff [DAYS]          compare the solutions with their brute-force references
+                       on --runs (default 100) generated inputs
+  minimize DAY PREDICATE
+                       shrink an input that makes DAY panic, fail with an error
+                       or disagree with its reference, PREDICATE being panic,
+                       error or disagreement; written to minimized-dayNN.txt
+  audit                show how both parts of day 1 read every line: the values,
+                       the first and last tokens with their byte offsets, and
+                       whether spelled digits changed the value
+
+options:
+  -a, --all           run every day (default when no day is given)
+  -i, --input PATH    read the puzzle input from PATH, `-` reads stdin
+      --stdin         read the puzzle input from stdin
+      --strict        reject inputs with CRLF line endings, a byte order mark,
+                      trailing whitespace or trailing blank lines instead of
+                      normalizing them
+      --lexicon PATH  recover day 1 part 2 digits from the `token value` pairs
+                      of PATH instead of the English spelled digits
+      --ignore-case   match the day 1 part 2 digit tokens case-insensitively
+      --missing-digits POLICY
+                      fail (default), skip or zero: what day 1 does with a
+                      line without digits, skipped lines are listed
+  -j, --parallel      solve the selected days concurrently
+  -f, --format FMT    print answers as text (default), json or csv
+      --record        store the computed answers as verified in the answers file
+      --overwrite     let --record replace verified answers that differ
+      --bench         time parsing and each part instead of printing answers
+      --warmup N      untimed runs before measuring (default 3)
+      --iterations N  timed runs per step (default 10)
+  -l, --list          list the solved days with their titles
+  -h, --help          print this message
+
+Inputs are read from $AOC_INPUT_DIR/dayNN.txt, or input/dayNN.txt when the
+variable is not set. Answers are checked against $AOC_ANSWERS_FILE, or
+answers.txt when the variable is not set. `fetch` reads the session token from
+$AOC_SESSION or ~/.config/aoc/session and downloads from --base-url, or
+$AOC_BASE_URL, or https://adventofcode.com. `submit` does the same and keeps
+every attempt in $AOC_ATTEMPTS_FILE, or attempts.txt, refusing answers that
+were already rejected or fall outside the known too high/too low bounds.
+`generate` draws from --seed (default 2023) and scales with --size (default
+100): lines, games, grid rows, cards, conversions per map or races (up to 3).";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunArgs),
+    NewDay {
+        day: u8,
+        title: String,
+        root: PathBuf,
+    },
+    Fetch {
+        days: Vec<u8>,
+        base_url: Option<String>,
+    },
+    Generate {
+        day: u8,
+        seed: u64,
+        size: usize,
+    },
+    Diff {
+        days: Vec<u8>,
+        seed: u64,
+        runs: u64,
+        size: usize,
+    },
+    Minimize {
+        day: u8,
+        predicate: Predicate,
+        input: InputSource,
+        output: Option<PathBuf>,
+    },
+    Audit {
+        input: InputSource,
+        format: Format,
+        lexicon: Option<PathBuf>,
+        ignore_case: bool,
+    },
+    Submit {
+        day: u8,
+        part: u8,
+        answer: Option<String>,
+        base_url: Option<String>,
+    },
+    List,
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunArgs {
+    pub days: Vec<u8>,
+    pub parts: Vec<u8>,
+    pub input: InputSource,
+    pub bench: Option<BenchOptions>,
+    pub parallel: bool,
+    pub format: Format,
+    pub record: bool,
+    pub overwrite: bool,
+    pub strict: bool,
+    pub lexicon: Option<PathBuf>,
+    pub ignore_case: bool,
+    pub missing_digits: MissingDigits,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+pub fn parse_args<I>(args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut all = false;
+    let mut days = None;
+    let mut parts = None;
+    let mut input = InputSource::Default;
+    let mut bench = false;
+    let mut parallel = false;
+    let mut format = Format::Text;
+    let mut record = false;
+    let mut overwrite = false;
+    let mut strict = false;
+    let mut lexicon = None;
+    let mut ignore_case = false;
+    let mut missing_digits = MissingDigits::Fail;
+    let mut warmups = None;
+    let mut iterations = None;
+
+    let mut args = args.into_iter().peekable();
+    if args.peek().map(String::as_str) == Some("new-day") {
+        args.next();
+        return parse_new_day(args, available_days);
+    }
+    if args.peek().map(String::as_str) == Some("fetch") {
+        args.next();
+        return parse_fetch(args);
+    }
+    if args.peek().map(String::as_str) == Some("submit") {
+        args.next();
+        return parse_submit(args, available_days);
+    }
+    if args.peek().map(String::as_str) == Some("generate") {
+        args.next();
+        return parse_generate(args, available_days);
+    }
+    if args.peek().map(String::as_str) == Some("diff") {
+        args.next();
+        return parse_diff(args, available_days);
+    }
+    if args.peek().map(String::as_str) == Some("minimize") {
+        args.next();
+        return parse_minimize(args, available_days);
+    }
+    if args.peek().map(String::as_str) == Some("audit") {
+        args.next();
+        return parse_audit(args, available_days);
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-l" | "--list" => return Ok(Command::List),
+            "-a" | "--all" => all = true,
+            "--stdin" => input = InputSource::Stdin,
+            "-i" | "--input" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a path", arg)))?;
+                input = if path == "-" {
+                    InputSource::Stdin
+                } else {
+                    InputSource::Path(PathBuf::from(path))
+                };
+            }
+            "-j" | "--parallel" => parallel = true,
+            "-f" | "--format" => {
+                let raw = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a format", arg)))?;
+                format = raw.parse().map_err(CliError)?;
+            }
+            "--record" => record = true,
+            "--overwrite" => overwrite = true,
+            "--strict" => strict = true,
+            "--lexicon" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a path", arg)))?;
+                lexicon = Some(PathBuf::from(path));
+            }
+            "--ignore-case" => ignore_case = true,
+            "--missing-digits" => {
+                let raw = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a policy", arg)))?;
+                missing_digits = raw.parse().map_err(CliError)?;
+            }
+            "--bench" => bench = true,
+            "--warmup" => warmups = Some(parse_count(&arg, args.next())?),
+            "--iterations" => iterations = Some(parse_count(&arg, args.next())?),
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            value if days.is_none() => days = Some(parse_days(value)?),
+            value if parts.is_none() => parts = Some(vec![parse_part(value)?]),
+            value => return Err(CliError(format!("unexpected argument {}", value))),
+        }
+    }
+
+    let days = match days {
+        Some(_) if all => return Err(CliError("--all can't be combined with DAYS".to_string())),
+        Some(days) => days,
+        None => available_days.to_vec(),
+    };
+    if let Some(missing) = days.iter().find(|day| !available_days.contains(day)) {
+        return Err(CliError(format!("day {} is not solved yet", missing)));
+    }
+    if input != InputSource::Default && days.len() != 1 {
+        return Err(CliError(
+            "an explicit input can only be used with a single day".to_string(),
+        ));
+    }
+
+    if overwrite && !record {
+        return Err(CliError(
+            "--overwrite only applies with --record".to_string(),
+        ));
+    }
+    if (lexicon.is_some() || ignore_case) && (days != [1] || bench || record) {
+        return Err(CliError(
+            "--lexicon and --ignore-case only apply to solving day 1 alone without --record"
+                .to_string(),
+        ));
+    }
+    if missing_digits != MissingDigits::Fail && (!days.contains(&1) || bench || record) {
+        return Err(CliError(
+            "--missing-digits only applies to solving day 1 without --record".to_string(),
+        ));
+    }
+
+    if parallel && bench {
+        return Err(CliError(
+            "--parallel can't be combined with --bench".to_string(),
+        ));
+    }
+    if format != Format::Text && bench {
+        return Err(CliError(
+            "--format can't be combined with --bench".to_string(),
+        ));
+    }
+    let bench = match (bench, warmups, iterations) {
+        (false, None, None) => None,
+        (false, _, _) => {
+            return Err(CliError(
+                "--warmup and --iterations require --bench".to_string(),
+            ))
+        }
+        (true, warmups, iterations) => {
+            let defaults = BenchOptions::default();
+            Some(BenchOptions {
+                warmups: warmups.unwrap_or(defaults.warmups),
+                iterations: iterations.unwrap_or(defaults.iterations),
+            })
+        }
+    };
+
+    Ok(Command::Run(RunArgs {
+        days,
+        parts: parts.unwrap_or_else(|| vec![1, 2]),
+        input,
+        bench,
+        parallel,
+        format,
+        record,
+        overwrite,
+        strict,
+        lexicon,
+        ignore_case,
+        missing_digits,
+    }))
+}
+
+fn parse_new_day<I>(mut args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut values = vec![];
+    let mut root = PathBuf::from(".");
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => {
+                root = PathBuf::from(
+                    args.next()
+                        .ok_or_else(|| CliError(format!("{} expects a path", arg)))?,
+                );
+            }
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            _ => values.push(arg),
+        }
+    }
+    let (day, title) = match &values[..] {
+        [day] => (parse_day(day)?, None),
+        [day, title] => (parse_day(day)?, Some(title.clone())),
+        [] => return Err(CliError("new-day expects a day".to_string())),
+        [_, _, extra, ..] => return Err(CliError(format!("unexpected argument {}", extra))),
+    };
+    if available_days.contains(&day) {
+        return Err(CliError(format!("day {} already exists", day)));
+    }
+    let title = title.unwrap_or_else(|| format!("Day {}", day));
+    Ok(Command::NewDay { day, title, root })
+}
+
+fn parse_fetch<I>(mut args: I) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut days = None;
+    let mut base_url = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--base-url" => {
+                base_url = Some(
+                    args.next()
+                        .ok_or_else(|| CliError(format!("{} expects a url", arg)))?,
+                );
+            }
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            value if days.is_none() => days = Some(parse_days(value)?),
+            value => return Err(CliError(format!("unexpected argument {}", value))),
+        }
+    }
+    let days = days.ok_or_else(|| CliError("fetch expects the days to download".to_string()))?;
+    Ok(Command::Fetch { days, base_url })
+}
+
+fn parse_submit<I>(mut args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut values = vec![];
+    let mut base_url = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--base-url" => {
+                base_url = Some(
+                    args.next()
+                        .ok_or_else(|| CliError(format!("{} expects a url", arg)))?,
+                );
+            }
+            flag if flag.starts_with('-') && flag.parse::<i64>().is_err() => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            _ => values.push(arg),
+        }
+    }
+    let (day, part, answer) = match &values[..] {
+        [day, part] => (parse_day(day)?, parse_part(part)?, None),
+        [day, part, answer] => (parse_day(day)?, parse_part(part)?, Some(answer.clone())),
+        [] | [_] => return Err(CliError("submit expects a day and a part".to_string())),
+        [_, _, _, extra, ..] => return Err(CliError(format!("unexpected argument {}", extra))),
+    };
+    if answer.is_none() && !available_days.contains(&day) {
+        return Err(CliError(format!(
+            "day {} is not solved yet, pass the answer to submit",
+            day
+        )));
+    }
+    Ok(Command::Submit {
+        day,
+        part,
+        answer,
+        base_url,
+    })
+}
+
+fn parse_generate<I>(mut args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut seed = generate::DEFAULT_SEED;
+    let mut size = generate::DEFAULT_SIZE;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = parse_seed(&arg, args.next())?,
+            "--size" => size = parse_count(&arg, args.next())?,
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            value if day.is_none() => day = Some(parse_day(value)?),
+            value => return Err(CliError(format!("unexpected argument {}", value))),
+        }
+    }
+    let day = day.ok_or_else(|| CliError("generate expects a day".to_string()))?;
+    if !available_days.contains(&day) {
+        return Err(CliError(format!("day {} is not solved yet", day)));
+    }
+    Ok(Command::Generate { day, seed, size })
+}
+
+fn parse_diff<I>(mut args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut days = None;
+    let mut seed = generate::DEFAULT_SEED;
+    let mut runs = differential::DEFAULT_RUNS;
+    let mut size = generate::DEFAULT_SIZE;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = parse_seed(&arg, args.next())?,
+            "--runs" => runs = parse_count(&arg, args.next())? as u64,
+            "--size" => size = parse_count(&arg, args.next())?,
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            value if days.is_none() => days = Some(parse_days(value)?),
+            value => return Err(CliError(format!("unexpected argument {}", value))),
+        }
+    }
+    let days = days.unwrap_or_else(|| available_days.to_vec());
+    if let Some(missing) = days.iter().find(|day| !available_days.contains(day)) {
+        return Err(CliError(format!("day {} is not solved yet", missing)));
+    }
+    if seed.checked_add(runs).is_none() {
+        return Err(CliError(
+            "--seed plus --runs must stay below 2^64".to_string(),
+        ));
+    }
+    Ok(Command::Diff {
+        days,
+        seed,
+        runs,
+        size,
+    })
+}
+
+fn parse_audit<I>(mut args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut input = InputSource::Default;
+    let mut format = Format::Text;
+    let mut lexicon = None;
+    let mut ignore_case = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stdin" => input = InputSource::Stdin,
+            "-i" | "--input" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a path", arg)))?;
+                input = if path == "-" {
+                    InputSource::Stdin
+                } else {
+                    InputSource::Path(PathBuf::from(path))
+                };
+            }
+            "-f" | "--format" => {
+                let raw = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a format", arg)))?;
+                format = raw.parse().map_err(CliError)?;
+            }
+            "--lexicon" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a path", arg)))?;
+                lexicon = Some(PathBuf::from(path));
+            }
+            "--ignore-case" => ignore_case = true,
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            value => return Err(CliError(format!("unexpected argument {}", value))),
+        }
+    }
+    if format == Format::Json {
+        return Err(CliError("audit prints text or csv".to_string()));
+    }
+    if !available_days.contains(&1) {
+        return Err(CliError("day 1 is not solved yet".to_string()));
+    }
+    Ok(Command::Audit {
+        input,
+        format,
+        lexicon,
+        ignore_case,
+    })
+}
+
+fn parse_minimize<I>(mut args: I, available_days: &[u8]) -> Result<Command, CliError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut predicate = None;
+    let mut input = InputSource::Default;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stdin" => input = InputSource::Stdin,
+            "-i" | "--input" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a path", arg)))?;
+                input = if path == "-" {
+                    InputSource::Stdin
+                } else {
+                    InputSource::Path(PathBuf::from(path))
+                };
+            }
+            "-o" | "--output" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError(format!("{} expects a path", arg)))?;
+                output = Some(PathBuf::from(path));
+            }
+            flag if flag.starts_with('-') => {
+                return Err(CliError(format!("unknown option {}", flag)))
+            }
+            value if day.is_none() => day = Some(parse_day(value)?),
+            value if predicate.is_none() => predicate = Some(value.parse().map_err(CliError)?),
+            value => return Err(CliError(format!("unexpected argument {}", value))),
+        }
+    }
+    let (Some(day), Some(predicate)) = (day, predicate) else {
+        return Err(CliError(
+            "minimize expects a day and a predicate".to_string(),
+        ));
+    };
+    if !available_days.contains(&day) {
+        return Err(CliError(format!("day {} is not solved yet", day)));
+    }
+    Ok(Command::Minimize {
+        day,
+        predicate,
+        input,
+        output,
+    })
+}
+
+fn parse_seed(flag: &str, raw: Option<String>) -> Result<u64, CliError> {
+    let raw = raw.ok_or_else(|| CliError(format!("{} expects a number", flag)))?;
+    raw.parse()
+        .map_err(|_| CliError(format!("invalid seed {}", raw)))
+}
+
+fn parse_count(flag: &str, raw: Option<String>) -> Result<usize, CliError> {
+    let raw = raw.ok_or_else(|| CliError(format!("{} expects a number", flag)))?;
+    raw.parse()
+        .map_err(|_| CliError(format!("invalid count {} for {}", raw, flag)))
+}
+
+fn parse_days(raw: &str) -> Result<Vec<u8>, CliError> {
+    if let Some((from, to)) = raw.split_once("..") {
+        let from = parse_day(from)?;
+        let to = parse_day(to.trim_start_matches('='))?;
+        if from > to {
+            return Err(CliError(format!("empty day range {}", raw)));
+        }
+        Ok((from..=to).collect())
+    } else {
+        raw.split(',').map(parse_day).collect()
+    }
+}
+
+fn parse_day(raw: &str) -> Result<u8, CliError> {
+    match raw.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(CliError(format!("invalid day {}", raw))),
+    }
+}
+
+fn parse_part(raw: &str) -> Result<u8, CliError> {
+    match raw {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(CliError(format!("invalid part {}", raw))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAYS: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|a| a.to_string()), &DAYS)
+    }
+
+    #[test]
+    fn day_and_part() {
+        assert_eq!(
+            parse(&["5", "2"]),
+            Ok(Command::Run(RunArgs {
+                days: vec![5],
+                parts: vec![2],
+                input: InputSource::Default,
+                bench: None,
+                parallel: false,
+                format: Format::Text,
+                record: false,
+                overwrite: false,
+                strict: false,
+                lexicon: None,
+                ignore_case: false,
+                missing_digits: MissingDigits::Fail,
+            }))
+        );
+    }
+
+    #[test]
+    fn ranges_lists_and_all() {
+        let days = |args: &[&str]| match parse(args) {
+            Ok(Command::Run(run)) => run.days,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(days(&["1..4"]), vec![1, 2, 3, 4]);
+        assert_eq!(days(&["2..=3"]), vec![2, 3]);
+        assert_eq!(days(&["1,3,6"]), vec![1, 3, 6]);
+        assert_eq!(days(&["--all"]), DAYS.to_vec());
+        assert_eq!(days(&[]), DAYS.to_vec());
+    }
+
+    #[test]
+    fn input_overrides() {
+        assert_eq!(
+            parse(&["3", "--input", "other.txt"]),
+            Ok(Command::Run(RunArgs {
+                days: vec![3],
+                parts: vec![1, 2],
+                input: InputSource::Path(PathBuf::from("other.txt")),
+                bench: None,
+                parallel: false,
+                format: Format::Text,
+                record: false,
+                overwrite: false,
+                strict: false,
+                lexicon: None,
+                ignore_case: false,
+                missing_digits: MissingDigits::Fail,
+            }))
+        );
+        assert!(matches!(
+            parse(&["3", "-i", "-"]),
+            Ok(Command::Run(RunArgs {
+                input: InputSource::Stdin,
+                ..
+            }))
+        ));
+        assert!(parse(&["--all", "--stdin"]).is_err());
+        assert!(matches!(
+            parse(&["3", "--strict"]),
+            Ok(Command::Run(RunArgs { strict: true, .. }))
+        ));
+    }
+
+    #[test]
+    fn audit_command() {
+        assert_eq!(
+            parse(&["audit", "--stdin", "-f", "csv", "--ignore-case"]),
+            Ok(Command::Audit {
+                input: InputSource::Stdin,
+                format: Format::Csv,
+                lexicon: None,
+                ignore_case: true,
+            })
+        );
+        assert!(parse(&["audit", "-f", "json"]).is_err());
+        assert!(parse(&["audit", "2"]).is_err());
+    }
+
+    #[test]
+    fn day01_lexicon() {
+        assert!(matches!(
+            parse(&["1", "2", "--lexicon", "german.txt", "--ignore-case"]),
+            Ok(Command::Run(RunArgs {
+                lexicon: Some(_),
+                ignore_case: true,
+                ..
+            }))
+        ));
+        assert!(parse(&["1..2", "--ignore-case"]).is_err());
+        assert!(parse(&["1", "--lexicon", "german.txt", "--bench"]).is_err());
+        assert!(parse(&["1", "--ignore-case", "--record"]).is_err());
+        assert!(matches!(
+            parse(&["1", "--record", "--overwrite"]),
+            Ok(Command::Run(RunArgs {
+                record: true,
+                overwrite: true,
+                ..
+            }))
+        ));
+        assert!(parse(&["1", "--overwrite"]).is_err());
+        assert!(parse(&["1", "--lexicon"]).is_err());
+        assert!(matches!(
+            parse(&["1", "--missing-digits", "skip"]),
+            Ok(Command::Run(RunArgs {
+                missing_digits: MissingDigits::Skip,
+                ..
+            }))
+        ));
+        assert!(parse(&["1", "--missing-digits", "ignore"]).is_err());
+        assert!(matches!(
+            parse(&["1..3", "--missing-digits", "zero"]),
+            Ok(Command::Run(RunArgs {
+                missing_digits: MissingDigits::Zero,
+                ..
+            }))
+        ));
+        assert!(parse(&["2", "--missing-digits", "zero"]).is_err());
+    }
+
+    #[test]
+    fn output_format() {
+        assert!(matches!(
+            parse(&["1..3", "-f", "csv"]),
+            Ok(Command::Run(RunArgs {
+                format: Format::Csv,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn bench_options() {
+        let bench = |args: &[&str]| match parse(args) {
+            Ok(Command::Run(run)) => run.bench,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(bench(&["1"]), None);
+        assert_eq!(bench(&["--bench"]), Some(BenchOptions::default()));
+        assert_eq!(
+            bench(&["2", "--bench", "--warmup", "0", "--iterations", "50"]),
+            Some(BenchOptions {
+                warmups: 0,
+                iterations: 50,
+            })
+        );
+        assert!(parse(&["--iterations", "5"]).is_err());
+        assert!(parse(&["--bench", "--warmup", "many"]).is_err());
+        assert!(parse(&["--bench", "--parallel"]).is_err());
+        assert!(parse(&["--bench", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn new_day() {
+        assert_eq!(
+            parse(&["new-day", "7", "Camel Cards"]),
+            Ok(Command::NewDay {
+                day: 7,
+                title: "Camel Cards".to_string(),
+                root: PathBuf::from("."),
+            })
+        );
+        assert_eq!(
+            parse(&["new-day", "8"]),
+            Ok(Command::NewDay {
+                day: 8,
+                title: "Day 8".to_string(),
+                root: PathBuf::from("."),
+            })
+        );
+        assert_eq!(
+            parse(&["new-day", "--root", "/tmp/aoc", "9", "Mirage Maintenance"]),
+            Ok(Command::NewDay {
+                day: 9,
+                title: "Mirage Maintenance".to_string(),
+                root: PathBuf::from("/tmp/aoc"),
+            })
+        );
+        assert!(parse(&["new-day", "9", "--root"]).is_err());
+        assert!(parse(&["new-day", "6"]).is_err());
+        assert!(parse(&["new-day"]).is_err());
+    }
+
+    #[test]
+    fn fetch() {
+        assert_eq!(
+            parse(&["fetch", "7..9"]),
+            Ok(Command::Fetch {
+                days: vec![7, 8, 9],
+                base_url: None,
+            })
+        );
+        assert_eq!(
+            parse(&["fetch", "--base-url", "http://localhost:8080", "2"]),
+            Ok(Command::Fetch {
+                days: vec![2],
+                base_url: Some("http://localhost:8080".to_string()),
+            })
+        );
+        assert!(parse(&["fetch"]).is_err());
+        assert!(parse(&["fetch", "26"]).is_err());
+        assert!(parse(&["fetch", "1", "--base-url"]).is_err());
+    }
+
+    #[test]
+    fn submit() {
+        assert_eq!(
+            parse(&["submit", "5", "2"]),
+            Ok(Command::Submit {
+                day: 5,
+                part: 2,
+                answer: None,
+                base_url: None,
+            })
+        );
+        assert_eq!(
+            parse(&["submit", "9", "1", "-114", "--base-url", "http://localhost"]),
+            Ok(Command::Submit {
+                day: 9,
+                part: 1,
+                answer: Some("-114".to_string()),
+                base_url: Some("http://localhost".to_string()),
+            })
+        );
+        assert!(parse(&["submit", "9", "1"]).is_err());
+        assert!(parse(&["submit", "5"]).is_err());
+        assert!(parse(&["submit", "5", "3"]).is_err());
+        assert!(parse(&["submit", "5", "1", "12", "13"]).is_err());
+    }
+
+    #[test]
+    fn generate() {
+        assert_eq!(
+            parse(&["generate", "3"]),
+            Ok(Command::Generate {
+                day: 3,
+                seed: generate::DEFAULT_SEED,
+                size: generate::DEFAULT_SIZE,
+            })
+        );
+        assert_eq!(
+            parse(&["generate", "--seed", "42", "5", "--size", "1000"]),
+            Ok(Command::Generate {
+                day: 5,
+                seed: 42,
+                size: 1000,
+            })
+        );
+        assert!(parse(&["generate"]).is_err());
+        assert!(parse(&["generate", "9"]).is_err());
+        assert!(parse(&["generate", "1", "--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn diff() {
+        assert_eq!(
+            parse(&["diff"]),
+            Ok(Command::Diff {
+                days: DAYS.to_vec(),
+                seed: generate::DEFAULT_SEED,
+                runs: differential::DEFAULT_RUNS,
+                size: generate::DEFAULT_SIZE,
+            })
+        );
+        assert_eq!(
+            parse(&["diff", "5", "--runs", "1000", "--size", "10", "--seed", "3"]),
+            Ok(Command::Diff {
+                days: vec![5],
+                seed: 3,
+                runs: 1000,
+                size: 10,
+            })
+        );
+        assert!(parse(&["diff", "1..9"]).is_err());
+        assert!(parse(&["diff", "--runs"]).is_err());
+        assert!(parse(&["diff", "--seed", "18446744073709551615", "--runs", "1"]).is_err());
+        assert!(parse(&["diff", "--seed", "18446744073709551614", "--runs", "1"]).is_ok());
+    }
+
+    #[test]
+    fn minimize() {
+        assert_eq!(
+            parse(&["minimize", "5", "disagreement"]),
+            Ok(Command::Minimize {
+                day: 5,
+                predicate: Predicate::Disagreement,
+                input: InputSource::Default,
+                output: None,
+            })
+        );
+        assert_eq!(
+            parse(&["minimize", "3", "panic", "-i", "big.txt", "-o", "small.txt"]),
+            Ok(Command::Minimize {
+                day: 3,
+                predicate: Predicate::Panic,
+                input: InputSource::Path(PathBuf::from("big.txt")),
+                output: Some(PathBuf::from("small.txt")),
+            })
+        );
+        assert!(parse(&["minimize", "3"]).is_err());
+        assert!(parse(&["minimize", "3", "slow"]).is_err());
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(parse(&["26"]).is_err());
+        assert!(parse(&["7"]).is_err());
+        assert!(parse(&["1", "3"]).is_err());
+        assert!(parse(&["4..2"]).is_err());
+        assert!(parse(&["1", "--bogus"]).is_err());
+        assert!(parse(&["1", "--all"]).is_err());
+        assert!(parse(&["1", "--format", "xml"]).is_err());
+    }
+}
@@ -1,100 +1,908 @@
-pub fn main() {
-    let input = include_str!("../input/day01.txt");
-    println!("PART 1: {}", part1(input));
-    println!("PART 2: {}", part2(input));
+use crate::automaton::{Automaton, Match};
+use crate::error::{self, ParseError, Unexpected};
+use crate::generate::{Generated, Rng};
+use crate::registry::Puzzle;
+use crate::report;
+use crate::{Answer, Solution};
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+const DAY: u8 = 1;
+
+pub const PUZZLE: Puzzle = Puzzle::new::<Day01>(DAY, "Trebuchet?!")
+    .with_generator(generate)
+    .with_reference(reference);
+
+/// The calibration document, one line per calibration value.
+///
+/// ```
+/// use advent_of_code::day01::{self, Document};
+///
+/// let document = "1abc2\ntwo1nine\n".parse::<Document>()?;
+/// assert_eq!(document.lines(), ["1abc2", "two1nine"]);
+/// assert_eq!(day01::solve_part1(&document)?, 23);
+/// assert_eq!(day01::solve_part2(&document)?, 41);
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Document {
+    lines: Vec<String>,
 }
 
-fn part1(input: &str) -> u32 {
-    input
-        .trim_end()
-        .split('\n')
-        .map(recover_calibration_value)
-        .sum()
+impl Document {
+    pub fn new(lines: Vec<String>) -> Document {
+        Document { lines }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+impl FromStr for Document {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Document, ParseError> {
+        Ok(Document::new(
+            input.trim_end().split('\n').map(str::to_string).collect(),
+        ))
+    }
 }
 
-fn recover_calibration_value(raw: &str) -> u32 {
-    let digits = raw
-        .chars()
-        .filter(|c| c.is_ascii_digit())
-        .map(|c| c.to_digit(10).unwrap())
-        .collect::<Vec<u32>>();
-    digits.first().unwrap() * 10 + digits.last().unwrap()
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Document;
+
+    fn parse(input: &str) -> Result<Document, ParseError> {
+        input.parse()
+    }
+
+    fn part1(document: &Document) -> Result<Answer, ParseError> {
+        solve_part1(document).map(Answer::from)
+    }
+
+    fn part2(document: &Document) -> Result<Answer, ParseError> {
+        solve_part2(document).map(Answer::from)
+    }
 }
 
-fn part2(input: &str) -> u32 {
-    input
-        .trim_end()
-        .split('\n')
-        .map(recover_spelled_calibration_value)
-        .sum()
+pub fn solve_part1(document: &Document) -> Result<u64, ParseError> {
+    static DIGITS: OnceLock<Lexicon> = OnceLock::new();
+    solve_with(document, DIGITS.get_or_init(Lexicon::digits))
 }
 
-fn recover_spelled_calibration_value(raw: &str) -> u32 {
-    let spelled_digits = vec![
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "1", "2", "3", "4",
-        "5", "6", "7", "8", "9",
-    ];
-    let (first_digit, _pos) = spelled_digits
+pub fn solve_part2(document: &Document) -> Result<u64, ParseError> {
+    static ENGLISH: OnceLock<Lexicon> = OnceLock::new();
+    solve_with(document, ENGLISH.get_or_init(Lexicon::english))
+}
+
+pub fn solve_with(document: &Document, lexicon: &Lexicon) -> Result<u64, ParseError> {
+    calibrate(document, lexicon, Extraction::PUZZLE, MissingDigits::Fail)
+        .map(|calibration| calibration.sum)
+}
+
+/// Which digits of a line make its calibration value.
+///
+/// ```
+/// use advent_of_code::day01::{Extraction, Lexicon};
+///
+/// let tokens = Lexicon::english().tokens_in("x1twone3");
+/// assert_eq!(Extraction::PUZZLE.digits(&tokens), Some(vec![1, 3]));
+/// assert_eq!(Extraction::first(2).digits(&tokens), Some(vec![1, 2]));
+/// assert_eq!(Extraction::All.digits(&tokens), Some(vec![1, 2, 1, 3]));
+/// assert_eq!(Extraction::last(5).digits(&tokens), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Extraction {
+    /// The first `first` digits followed by the last `last` digits, which
+    /// overlap on lines with few digits.
+    Ends {
+        first: usize,
+        last: usize,
+    },
+    All,
+}
+
+impl Extraction {
+    pub const PUZZLE: Extraction = Extraction::Ends { first: 1, last: 1 };
+
+    pub fn first(count: usize) -> Extraction {
+        Extraction::Ends {
+            first: count,
+            last: 0,
+        }
+    }
+
+    pub fn last(count: usize) -> Extraction {
+        Extraction::Ends {
+            first: 0,
+            last: count,
+        }
+    }
+
+    /// The digits making the value, or `None` when the line has too few of
+    /// them; every extraction needs at least one.
+    pub fn digits(self, tokens: &[Token<'_>]) -> Option<Vec<u32>> {
+        self.select(tokens).map(Iterator::collect)
+    }
+
+    fn select<'t>(self, tokens: &'t [Token<'_>]) -> Option<impl Iterator<Item = u32> + 't> {
+        let (first, last) = match self {
+            Extraction::Ends { first, last } => (first, last),
+            Extraction::All => (tokens.len(), 0),
+        };
+        let values = tokens.iter().map(|token| token.value);
+        (tokens.len() >= first.max(last).max(1)).then(|| {
+            values
+                .clone()
+                .take(first)
+                .chain(values.skip(tokens.len() - last))
+        })
+    }
+}
+
+/// The number written with `digits`, or `None` when it doesn't fit in 64 bits.
+pub fn concatenate(digits: impl IntoIterator<Item = u32>) -> Option<u64> {
+    digits.into_iter().try_fold(0_u64, |number, digit| {
+        number.checked_mul(10)?.checked_add(u64::from(digit))
+    })
+}
+
+/// What to do with a line where no token of the lexicon appears.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingDigits {
+    #[default]
+    Fail,
+    Skip,
+    Zero,
+}
+
+impl FromStr for MissingDigits {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<MissingDigits, String> {
+        match raw {
+            "fail" => Ok(MissingDigits::Fail),
+            "skip" => Ok(MissingDigits::Skip),
+            "zero" => Ok(MissingDigits::Zero),
+            _ => Err(format!(
+                "unknown policy {}, expected fail, skip or zero",
+                raw
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Calibration {
+    pub sum: u64,
+    pub policy: MissingDigits,
+    /// The 1-based numbers of the lines without digits that were skipped or
+    /// counted as zero.
+    pub missing: Vec<usize>,
+}
+
+impl Calibration {
+    pub fn summary(&self) -> Option<String> {
+        if self.missing.is_empty() {
+            return None;
+        }
+        let lines = self
+            .missing
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let plural = if self.missing.len() == 1 { "" } else { "s" };
+        Some(match self.policy {
+            MissingDigits::Zero => format!(
+                "counted {} line{} without digits as zero: {}",
+                self.missing.len(),
+                plural,
+                lines
+            ),
+            _ => format!(
+                "skipped {} line{} without digits: {}",
+                self.missing.len(),
+                plural,
+                lines
+            ),
+        })
+    }
+}
+
+/// The calibration value of each line, or where the line lacks a digit.
+pub fn calibration_values<'a>(
+    document: &'a Document,
+    lexicon: &'a Lexicon,
+    extraction: Extraction,
+) -> impl Iterator<Item = Result<u64, ParseError>> + 'a {
+    document.lines.iter().enumerate().map(move |(index, line)| {
+        match line_value(line, lexicon, extraction) {
+            Ok(value) => Ok(value),
+            Err(LineError::Missing(e) | LineError::Overflow(e)) => {
+                Err(e.locate_line(DAY, index + 1, line))
+            }
+        }
+    })
+}
+
+pub fn calibrate(
+    document: &Document,
+    lexicon: &Lexicon,
+    extraction: Extraction,
+    policy: MissingDigits,
+) -> Result<Calibration, ParseError> {
+    let mut calibration = Calibration {
+        sum: 0,
+        policy,
+        missing: vec![],
+    };
+    for (index, line) in document.lines.iter().enumerate() {
+        let value = match (line_value(line, lexicon, extraction), policy) {
+            (Ok(value), _) => value,
+            (Err(LineError::Missing(_)), MissingDigits::Skip | MissingDigits::Zero) => {
+                calibration.missing.push(index + 1);
+                continue;
+            }
+            (Err(LineError::Missing(e) | LineError::Overflow(e)), _) => {
+                return Err(e.locate_line(DAY, index + 1, line))
+            }
+        };
+        calibration.sum = calibration.sum.checked_add(value).ok_or_else(|| {
+            ParseError::whole_input(DAY, "a sum of calibration values below 2^64")
+        })?;
+    }
+    Ok(calibration)
+}
+
+enum LineError<'a> {
+    Missing(Unexpected<'a>),
+    Overflow(Unexpected<'a>),
+}
+
+fn line_value<'a>(
+    line: &'a str,
+    lexicon: &Lexicon,
+    extraction: Extraction,
+) -> Result<u64, LineError<'a>> {
+    let (pair, scanned);
+    let tokens: &[Token<'_>] = match extraction {
+        // The first and last tokens are all these need, which spares scanning
+        // the whole line.
+        Extraction::Ends {
+            first: 0..=1,
+            last: 0..=1,
+        } => match lexicon.recover(line) {
+            Some(recovery) => {
+                pair = [recovery.first, recovery.last];
+                &pair[..if recovery.first == recovery.last {
+                    1
+                } else {
+                    2
+                }]
+            }
+            None => &[],
+        },
+        _ => {
+            scanned = lexicon.tokens_in(line);
+            &scanned
+        }
+    };
+    let digits = extraction.select(tokens).ok_or_else(|| {
+        let expected = if tokens.is_empty() {
+            lexicon.expected
+        } else {
+            "enough digits for the extraction"
+        };
+        LineError::Missing(Unexpected::end_of(line, expected))
+    })?;
+    concatenate(digits)
+        .ok_or_else(|| LineError::Overflow(Unexpected::new(line, "a calibration value below 2^64")))
+}
+
+/// The tokens recognized as digits when recovering calibration values.
+///
+/// A lexicon file has one `token value` pair per line, blank lines and lines
+/// starting with `#` are ignored.
+///
+/// ```
+/// use advent_of_code::day01::{self, Document, Lexicon};
+///
+/// let document = "zwei1drei\nNULLacht\n".parse::<Document>()?;
+/// let german = "null 0\neins 1\nzwei 2\ndrei 3\nacht 8\n1 1".parse::<Lexicon>()?;
+/// assert_eq!(day01::solve_with(&document, &german)?, 23 + 88);
+/// let german = german.ignore_case().unwrap();
+/// assert_eq!(day01::solve_with(&document, &german)?, 23 + 8);
+///
+/// let lexicon = Lexicon::english().with_token("zero", 0).unwrap();
+/// assert_eq!(lexicon.calibration_value("zeroxseven"), Some(7));
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lexicon {
+    tokens: Vec<(String, u32)>,
+    ignore_case: bool,
+    expected: &'static str,
+    automaton: Automaton,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LexiconError {
+    EmptyToken,
+    NotADigit { token: String, value: u32 },
+    Conflict { token: String },
+}
+
+impl fmt::Display for LexiconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexiconError::EmptyToken => write!(f, "lexicon tokens can't be empty"),
+            LexiconError::NotADigit { token, value } => {
+                write!(f, "{} stands for {}, which isn't a digit", token, value)
+            }
+            LexiconError::Conflict { token } => {
+                write!(f, "{} stands for two different digits", token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LexiconError {}
+
+impl Lexicon {
+    pub fn new<T: Into<String>>(
+        tokens: impl IntoIterator<Item = (T, u32)>,
+    ) -> Result<Lexicon, LexiconError> {
+        let tokens = tokens
+            .into_iter()
+            .map(|(token, value)| (token.into(), value))
+            .collect();
+        Lexicon::build(tokens, false, "a lexicon token")
+    }
+
+    pub fn digits() -> Lexicon {
+        let tokens = (0..=9).map(|digit| (digit.to_string(), digit)).collect();
+        Lexicon::build(tokens, false, "a digit").expect("digits are valid tokens")
+    }
+
+    pub fn english() -> Lexicon {
+        let tokens = WORDS
+            .iter()
+            .map(|word| word.to_string())
+            .chain((1..=9).map(|digit: u32| digit.to_string()))
+            .zip((1..=9).cycle())
+            .collect();
+        Lexicon::build(tokens, false, "a digit or a spelled digit")
+            .expect("spelled digits are valid tokens")
+    }
+
+    pub fn with_token(
+        mut self,
+        token: impl Into<String>,
+        value: u32,
+    ) -> Result<Lexicon, LexiconError> {
+        self.tokens.push((token.into(), value));
+        Lexicon::build(self.tokens, self.ignore_case, self.expected)
+    }
+
+    pub fn ignore_case(self) -> Result<Lexicon, LexiconError> {
+        Lexicon::build(self.tokens, true, self.expected)
+    }
+
+    pub fn tokens(&self) -> &[(String, u32)] {
+        &self.tokens
+    }
+
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        self.recover(line).map(|recovery| recovery.value())
+    }
+
+    pub fn recover<'a>(&self, line: &'a str) -> Option<Recovery<'a>> {
+        if !self.ignore_case {
+            let (first, last) = (self.automaton.first(line)?, self.automaton.last(line)?);
+            return Some(Recovery {
+                first: self.token(line, first.pattern, first.start, first.end),
+                last: self.token(line, last.pattern, last.start, last.end),
+            });
+        }
+        let found = self.locate(line, |haystack| {
+            let first = self.automaton.first(haystack);
+            first
+                .into_iter()
+                .chain(self.automaton.last(haystack))
+                .collect()
+        });
+        Some(Recovery {
+            first: *found.first()?,
+            last: *found.last()?,
+        })
+    }
+
+    /// Every token of the line in order, the longest one where several start
+    /// at the same offset.
+    pub fn tokens_in<'a>(&self, line: &'a str) -> Vec<Token<'a>> {
+        let mut tokens = self.locate(line, |haystack| self.automaton.all(haystack));
+        tokens.dedup_by_key(|token| token.start);
+        tokens
+    }
+
+    fn locate<'a>(&self, line: &'a str, scan: impl Fn(&str) -> Vec<Match>) -> Vec<Token<'a>> {
+        if !self.ignore_case {
+            return scan(line)
+                .into_iter()
+                .map(|found| self.token(line, found.pattern, found.start, found.end))
+                .collect();
+        }
+        // Lowercasing can change the length of a character, so every folded
+        // byte remembers the span of the character it comes from.
+        let mut folded = String::with_capacity(line.len());
+        let mut spans = Vec::with_capacity(line.len());
+        for (start, c) in line.char_indices() {
+            let span = (start, start + c.len_utf8());
+            for lower in c.to_lowercase() {
+                folded.push(lower);
+                spans.resize(folded.len(), span);
+            }
+        }
+        scan(&folded)
+            .into_iter()
+            .map(|found| {
+                let (start, end) = (spans[found.start].0, spans[found.end - 1].1);
+                self.token(line, found.pattern, start, end)
+            })
+            .collect()
+    }
+
+    fn token<'a>(&self, line: &'a str, pattern: usize, start: usize, end: usize) -> Token<'a> {
+        Token {
+            text: &line[start..end],
+            value: self.tokens[pattern].1,
+            start,
+        }
+    }
+
+    fn build(
+        tokens: Vec<(String, u32)>,
+        ignore_case: bool,
+        expected: &'static str,
+    ) -> Result<Lexicon, LexiconError> {
+        let mut seen = HashMap::new();
+        let mut patterns = Vec::with_capacity(tokens.len());
+        for (token, value) in &tokens {
+            if token.is_empty() {
+                return Err(LexiconError::EmptyToken);
+            }
+            if *value > 9 {
+                return Err(LexiconError::NotADigit {
+                    token: token.clone(),
+                    value: *value,
+                });
+            }
+            let pattern = if ignore_case {
+                token.to_lowercase()
+            } else {
+                token.clone()
+            };
+            if *seen.entry(pattern.clone()).or_insert(*value) != *value {
+                return Err(LexiconError::Conflict {
+                    token: token.clone(),
+                });
+            }
+            patterns.push(pattern);
+        }
+        Ok(Lexicon {
+            automaton: Automaton::new(&patterns),
+            tokens,
+            ignore_case,
+            expected,
+        })
+    }
+}
+
+/// A token of the lexicon found in a line, `start` being its byte offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub value: u32,
+    pub start: usize,
+}
+
+/// The first and last tokens of a line, which make its calibration value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Recovery<'a> {
+    pub first: Token<'a>,
+    pub last: Token<'a>,
+}
+
+impl Recovery<'_> {
+    pub fn value(&self) -> u32 {
+        self.first.value * 10 + self.last.value
+    }
+}
+
+impl FromStr for Lexicon {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Lexicon, ParseError> {
+        let tokens = parse_lexicon(input).map_err(|e| e.locate(DAY, input))?;
+        Ok(Lexicon::new(tokens).expect("lexicon entries are checked while parsing"))
+    }
+}
+
+fn parse_lexicon(input: &str) -> Result<Vec<(&str, u32)>, Unexpected<'_>> {
+    let mut tokens: Vec<(&str, u32)> = vec![];
+    for line in input.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let token = fields.next().expect("blank lines are skipped");
+        let raw_value = fields
+            .next()
+            .ok_or_else(|| Unexpected::end_of(line, "a digit value after the token"))?;
+        if let Some(extra) = fields.next() {
+            return Err(Unexpected::new(extra, "a single token and value per line"));
+        }
+        let value = error::number(raw_value)?;
+        if value > 9 {
+            return Err(Unexpected::new(raw_value, "a digit value from 0 to 9"));
+        }
+        if tokens
+            .iter()
+            .any(|&(seen, other)| seen == token && other != value)
+        {
+            return Err(Unexpected::new(
+                token,
+                "a token that doesn't stand for another digit",
+            ));
+        }
+        tokens.push((token, value));
+    }
+    if tokens.is_empty() {
+        return Err(Unexpected::end_of(input.trim_end(), "at least one token"));
+    }
+    Ok(tokens)
+}
+
+/// How both parts read one line of the document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuditLine<'a> {
+    pub line: usize,
+    pub part1: Option<Recovery<'a>>,
+    pub part2: Option<Recovery<'a>>,
+}
+
+impl AuditLine<'_> {
+    /// Whether the spelled tokens of part 2 give the line another value.
+    pub fn changed(&self) -> bool {
+        self.part1.map(|r| r.value()) != self.part2.map(|r| r.value())
+    }
+}
+
+pub fn audit<'a>(document: &'a Document, part1: &Lexicon, part2: &Lexicon) -> Vec<AuditLine<'a>> {
+    document
+        .lines
         .iter()
-        .map(|digit| (digit, raw.find(digit)))
-        .filter(|(_d, optional_pos)| optional_pos.is_some())
-        .min_by_key(|(_d, pos)| pos.unwrap())
-        .unwrap();
+        .enumerate()
+        .map(|(index, line)| AuditLine {
+            line: index + 1,
+            part1: part1.recover(line),
+            part2: part2.recover(line),
+        })
+        .collect()
+}
 
-    let (last_digit, _pos) = spelled_digits
+pub fn render_audit_table(lines: &[AuditLine<'_>]) -> String {
+    let header = [
+        "line", "part 1", "first", "last", "part 2", "first", "last", "changed",
+    ];
+    let rows = lines
         .iter()
-        .map(|digit| (digit, raw.rfind(digit)))
-        .filter(|(_d, optional_pos)| optional_pos.is_some())
-        .max_by_key(|(_d, pos)| pos.unwrap())
-        .unwrap();
+        .map(|audited| {
+            let mut row = vec![audited.line.to_string()];
+            for recovery in [audited.part1, audited.part2] {
+                let token = |token: Token<'_>| format!("{}@{}", token.text, token.start);
+                row.extend(match recovery {
+                    Some(r) => [r.value().to_string(), token(r.first), token(r.last)],
+                    None => ["-".to_string(), "-".to_string(), "-".to_string()],
+                });
+            }
+            row.push(if audited.changed() { "*" } else { "" }.to_string());
+            row
+        })
+        .collect::<Vec<_>>();
+    let widths = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let mut table = String::new();
+    let header = header.map(str::to_string);
+    for row in std::iter::once(&header[..]).chain(rows.iter().map(Vec::as_slice)) {
+        let mut rendered = String::new();
+        for (cell, width) in row.iter().zip(&widths) {
+            write!(rendered, "{:<width$}  ", cell, width = width).unwrap();
+        }
+        writeln!(table, "{}", rendered.trim_end()).unwrap();
+    }
+    table
+}
+
+pub fn render_audit_csv(lines: &[AuditLine<'_>]) -> String {
+    let mut csv = String::from(
+        "line,part1,part1_first,part1_first_start,part1_last,part1_last_start,\
+         part2,part2_first,part2_first_start,part2_last,part2_last_start,changed\n",
+    );
+    for audited in lines {
+        write!(csv, "{}", audited.line).unwrap();
+        for recovery in [audited.part1, audited.part2] {
+            match recovery {
+                Some(r) => write!(
+                    csv,
+                    ",{},{},{},{},{}",
+                    r.value(),
+                    report::csv_field(r.first.text),
+                    r.first.start,
+                    report::csv_field(r.last.text),
+                    r.last.start
+                )
+                .unwrap(),
+                None => csv.push_str(",,,,,"),
+            }
+        }
+        writeln!(csv, ",{}", audited.changed()).unwrap();
+    }
+    csv
+}
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
 
-    translate_spelled_digit(first_digit) * 10 + translate_spelled_digit(last_digit)
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    const FILLER: &[u8] = b"abcdjklmpqyz";
+    let push_filler = |rng: &mut Rng, line: &mut String, min: u64| {
+        for _ in 0..rng.between(min, 3) {
+            line.push(*rng.pick(FILLER) as char);
+        }
+    };
+
+    let mut input = String::new();
+    let (mut part1, mut part2) = (0, 0);
+    for _ in 0..size.max(1) {
+        let tokens = rng.between(1, 6);
+        let numeric = rng.below(tokens);
+        let (mut digits, mut values) = (vec![], vec![]);
+        for token in 0..tokens {
+            push_filler(rng, &mut input, u64::from(token > 0));
+            let digit = rng.between(1, 9);
+            if token == numeric || rng.chance(50) {
+                input.push_str(&digit.to_string());
+                digits.push(digit);
+            } else {
+                input.push_str(WORDS[digit as usize - 1]);
+            }
+            values.push(digit);
+        }
+        push_filler(rng, &mut input, 0);
+        input.push('\n');
+        part1 += digits[0] * 10 + digits[digits.len() - 1];
+        part2 += values[0] * 10 + values[values.len() - 1];
+    }
+    Generated {
+        input,
+        expected: vec![(1, Answer::from(part1)), (2, Answer::from(part2))],
+    }
 }
 
-fn translate_spelled_digit(digit: &str) -> u32 {
-    match digit {
-        "one" => 1,
-        "two" => 2,
-        "three" => 3,
-        "four" => 4,
-        "five" => 5,
-        "six" => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine" => 9,
-        n => n.parse::<u32>().unwrap(),
+fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+    let mut sum = 0;
+    for line in input.trim_end().split('\n') {
+        let digits = line
+            .char_indices()
+            .filter_map(|(i, c)| {
+                c.to_digit(10).or_else(|| {
+                    (part == 2)
+                        .then(|| WORDS.iter().position(|word| line[i..].starts_with(word)))
+                        .flatten()
+                        .map(|position| position as u32 + 1)
+                })
+            })
+            .collect::<Vec<_>>();
+        match (digits.first(), digits.last()) {
+            (Some(first), Some(last)) => sum += first * 10 + last,
+            _ => return Err(Unexpected::end_of(line, "a digit").locate(DAY, input)),
+        }
     }
+    Ok(Answer::from(sum))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers;
 
     #[test]
-    fn sample_inputs_part_1() {
-        let input = "1abc2
-pqr3stu8vwx
-a1b2c3d4e5f
-treb7uchet";
-        assert_eq!(part1(input), 142);
+    fn solutions() {
+        answers::assert_solution::<Day01>(DAY, 1);
+        answers::assert_solution::<Day01>(DAY, 2);
     }
 
     #[test]
-    fn sample_inputs_part_2() {
-        let input = "two1nine
-eightwothree
-abcone2threexyz
-xtwone3four
-4nineeightseven2
-zoneight234
-7pqrstsixteen";
-        assert_eq!(part2(input), 281);
+    fn line_without_digits() {
+        let document = "1abc2\npqrstu\n".parse::<Document>().unwrap();
+        let error = solve_part1(&document).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "a digit");
     }
 
     #[test]
-    fn solutions() {
-        let input = include_str!("../input/day01.txt");
-        assert_eq!(part1(input), 54561);
-        assert_eq!(part2(input), 54076);
+    fn missing_digits_policies() {
+        let document = "1abc2\npqrstu\nx7\nnone\n".parse::<Document>().unwrap();
+        let calibrate =
+            |policy| calibrate(&document, &Lexicon::digits(), Extraction::PUZZLE, policy);
+        let error = calibrate(MissingDigits::Fail).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        let skipped = calibrate(MissingDigits::Skip).unwrap();
+        assert_eq!((skipped.sum, &skipped.missing[..]), (12 + 77, &[2, 4][..]));
+        assert_eq!(
+            skipped.summary().as_deref(),
+            Some("skipped 2 lines without digits: 2, 4")
+        );
+        let zeroed = calibrate(MissingDigits::Zero).unwrap();
+        assert_eq!(
+            zeroed.summary().as_deref(),
+            Some("counted 2 lines without digits as zero: 2, 4")
+        );
+        let english = super::calibrate(
+            &document,
+            &Lexicon::english(),
+            Extraction::PUZZLE,
+            MissingDigits::Zero,
+        );
+        assert_eq!(
+            english.map(|c| (c.sum, c.missing)),
+            Ok((12 + 77 + 11, vec![2]))
+        );
+    }
+
+    #[test]
+    fn generalized_extractions() {
+        let document = "a1b22c333\n9\n".parse::<Document>().unwrap();
+        let digits = Lexicon::digits();
+        let values = |extraction| {
+            calibration_values(&document, &digits, extraction)
+                .map(|value| value.map_err(|e| (e.line, e.expected)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(Extraction::PUZZLE), [Ok(13), Ok(99)]);
+        assert_eq!(values(Extraction::All), [Ok(122333), Ok(9)]);
+        assert_eq!(
+            values(Extraction::Ends { first: 2, last: 3 }),
+            [
+                Ok(12333),
+                Err((2, "enough digits for the extraction".to_string()))
+            ]
+        );
+        let long = "9".repeat(20).parse::<Document>().unwrap();
+        let error = calibrate(&long, &digits, Extraction::All, MissingDigits::Skip).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.expected, "a calibration value below 2^64");
+        assert_eq!(concatenate([1; 19]), Some(1_111_111_111_111_111_111));
+    }
+
+    #[test]
+    fn audit_reports() {
+        let document = "two1nine\nabc\nx4\n".parse::<Document>().unwrap();
+        let audited = audit(&document, &Lexicon::digits(), &Lexicon::english());
+        assert_eq!(
+            audited.iter().map(AuditLine::changed).collect::<Vec<_>>(),
+            [true, false, false]
+        );
+        assert_eq!(
+            render_audit_table(&audited),
+            "line  part 1  first  last  part 2  first  last    changed
+1     11      1@3    1@3   29      two@0  nine@4  *
+2     -       -      -     -       -      -
+3     44      4@1    4@1   44      4@1    4@1
+"
+        );
+        assert_eq!(
+            render_audit_csv(&audited).lines().nth(2),
+            Some("2,,,,,,,,,,,false")
+        );
+        assert_eq!(
+            render_audit_csv(&audited).lines().nth(1),
+            Some("1,11,1,3,1,3,29,two,0,nine,4,true")
+        );
+    }
+
+    #[test]
+    fn case_insensitive_offsets() {
+        let lexicon = Lexicon::new([("İ", 1), ("two", 2)])
+            .unwrap()
+            .ignore_case()
+            .unwrap();
+        let recovery = lexicon.recover("xİTWO").unwrap();
+        assert_eq!((recovery.first.text, recovery.first.start), ("İ", 1));
+        assert_eq!((recovery.last.text, recovery.last.start), ("TWO", 3));
+        assert_eq!(recovery.value(), 12);
+    }
+
+    #[test]
+    fn overlapping_spelled_digits() {
+        let document = "eightwothree\nxtwone3four\noneight\n"
+            .parse::<Document>()
+            .unwrap();
+        assert_eq!(solve_part2(&document), Ok(83 + 24 + 18));
+    }
+
+    #[test]
+    fn custom_lexicons() {
+        let document = "Zero7uno\nDOS\n".parse::<Document>().unwrap();
+        let spanish = Lexicon::new([("cero", 0), ("uno", 1), ("dos", 2), ("7", 7)]).unwrap();
+        let error = solve_with(&document, &spanish).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "a lexicon token");
+        let spanish = spanish.ignore_case().unwrap();
+        assert_eq!(solve_with(&document, &spanish), Ok(71 + 22));
+        let english = Lexicon::english().with_token("zero", 0).unwrap();
+        assert_eq!(english.calibration_value("zero7uno"), Some(7));
+        assert_eq!(
+            english.ignore_case().unwrap().calibration_value("Zero7"),
+            Some(7)
+        );
+        assert_eq!(
+            Lexicon::new([("Dos", 2), ("dos", 3)])
+                .unwrap()
+                .ignore_case()
+                .err(),
+            Some(LexiconError::Conflict {
+                token: "dos".to_string()
+            })
+        );
+        assert_eq!(
+            Lexicon::digits().with_token("", 1).err(),
+            Some(LexiconError::EmptyToken)
+        );
+    }
+
+    #[test]
+    fn malformed_lexicon_file() {
+        let located = |input: &str| {
+            input
+                .parse::<Lexicon>()
+                .map_err(|e| (e.line, e.column, e.expected))
+                .err()
+        };
+        assert_eq!(located("# german\n\neins 1\nzwei 2\n"), None);
+        assert_eq!(
+            located("eins 1\nzwei 12\n"),
+            Some((2, 6, "a digit value from 0 to 9".to_string()))
+        );
+        assert_eq!(
+            located("eins\n"),
+            Some((1, 5, "a digit value after the token".to_string()))
+        );
+        assert_eq!(
+            located("eins 1\neins 2\n"),
+            Some((
+                2,
+                1,
+                "a token that doesn't stand for another digit".to_string()
+            ))
+        );
+        assert_eq!(
+            located("# nothing\n"),
+            Some((1, 10, "at least one token".to_string()))
+        );
     }
 }
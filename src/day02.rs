@@ -1,118 +1,271 @@
+use crate::error::{self, ParseError, Unexpected};
+use crate::generate::{Generated, Rng};
+use crate::registry::Puzzle;
+use crate::{Answer, Solution};
 use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
 
-struct Set {
-    red: u32,
-    green: u32,
-    blue: u32,
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Set {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
 }
 
-struct Game {
-    id: u32,
-    sets: Vec<Set>,
+impl Set {
+    pub fn new(red: u32, green: u32, blue: u32) -> Set {
+        Set { red, green, blue }
+    }
+
+    pub fn power(&self) -> Option<u64> {
+        u64::from(self.red)
+            .checked_mul(u64::from(self.green))?
+            .checked_mul(u64::from(self.blue))
+    }
 }
 
-pub fn main() {
-    let input = include_str!("../input/day02.txt");
-    println!("PART 1: {}", part1(input));
-    println!("PART 2: {}", part2(input));
+impl FromStr for Set {
+    type Err = ParseError;
+
+    fn from_str(encoded_set: &str) -> Result<Set, ParseError> {
+        parse_set(encoded_set).map_err(|e| e.locate(DAY, encoded_set))
+    }
 }
 
-fn part1(input: &str) -> u32 {
-    input
-        .trim_end()
-        .split('\n')
-        .map(parse_game)
+/// A game and the sets of cubes revealed during it.
+///
+/// ```
+/// use advent_of_code::day02::{self, Game, Set};
+///
+/// let game = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".parse::<Game>()?;
+/// assert_eq!(game.id, 1);
+/// assert_eq!(game.minimum_set(), Set::new(4, 2, 6));
+///
+/// let games = day02::parse_games("Game 1: 3 blue, 4 red\nGame 2: 20 red\n")?;
+/// assert_eq!(day02::solve_part1(&games)?, 1);
+/// assert_eq!(day02::solve_part2(&games)?, 0);
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub sets: Vec<Set>,
+}
+
+impl Game {
+    pub fn new(id: u32, sets: Vec<Set>) -> Game {
+        Game { id, sets }
+    }
+
+    pub fn minimum_set(&self) -> Set {
+        let red = self.sets.iter().map(|set| set.red).max().unwrap_or(0);
+        let green = self.sets.iter().map(|set| set.green).max().unwrap_or(0);
+        let blue = self.sets.iter().map(|set| set.blue).max().unwrap_or(0);
+        Set { red, green, blue }
+    }
+}
+
+impl FromStr for Game {
+    type Err = ParseError;
+
+    fn from_str(encoded_game: &str) -> Result<Game, ParseError> {
+        parse_game(encoded_game).map_err(|e| e.locate(DAY, encoded_game))
+    }
+}
+
+const DAY: u8 = 2;
+
+pub const PUZZLE: Puzzle = Puzzle::new::<Day02>(DAY, "Cube Conundrum")
+    .with_generator(generate)
+    .with_reference(reference);
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+        parse_games(input)
+    }
+
+    fn part1(games: &Vec<Game>) -> Result<Answer, ParseError> {
+        solve_part1(games).map(Answer::from)
+    }
+
+    fn part2(games: &Vec<Game>) -> Result<Answer, ParseError> {
+        solve_part2(games).map(Answer::from)
+    }
+}
+
+pub fn solve_part1(games: &[Game]) -> Result<u64, ParseError> {
+    Ok(games
+        .iter()
         .filter_map(|game| {
             game.sets
                 .iter()
                 .all(|set| set.red <= 12 && set.green <= 13 && set.blue <= 14)
-                .then_some(game.id)
+                .then_some(u64::from(game.id))
         })
-        .sum()
+        .sum())
 }
 
-fn part2(input: &str) -> u32 {
+pub fn solve_part2(games: &[Game]) -> Result<u64, ParseError> {
+    games
+        .iter()
+        .map(|game| game.minimum_set().power())
+        .try_fold(0_u64, |sum, power| sum.checked_add(power?))
+        .ok_or_else(|| ParseError::whole_input(DAY, "a sum of powers below 2^64"))
+}
+
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
     input
         .trim_end()
         .split('\n')
         .map(parse_game)
-        .map(|game| {
-            let set_of_cubes = minimum_set(game);
-            power_of_set(set_of_cubes)
-        })
-        .sum()
-}
-
-fn minimum_set(game: Game) -> Set {
-    let red = game.sets.iter().map(|set| set.red).max().unwrap_or(0);
-    let green = game.sets.iter().map(|set| set.green).max().unwrap_or(0);
-    let blue = game.sets.iter().map(|set| set.blue).max().unwrap_or(0);
-    Set { red, green, blue }
-}
-
-fn power_of_set(set: Set) -> u32 {
-    set.red * set.green * set.blue
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.locate(DAY, input))
 }
 
-fn parse_game(encoded_game: &str) -> Game {
-    let splitted_game = encoded_game.splitn(2, ':').collect::<Vec<&str>>();
-    let header = splitted_game.first().unwrap();
-    let extractions = splitted_game.last().unwrap();
-    let id = header.replace("Game ", "").parse::<u32>().unwrap();
-    let sets = extractions.split(';').map(parse_set).collect();
-    Game { id, sets }
+fn parse_game(encoded_game: &str) -> Result<Game, Unexpected<'_>> {
+    let (header, extractions) = error::split_once(encoded_game, ":", "':'")?;
+    let id = header
+        .strip_prefix("Game ")
+        .ok_or_else(|| Unexpected::new(header, "'Game '"))?;
+    let id = error::number(id)?;
+    let sets = extractions
+        .split(';')
+        .map(parse_set)
+        .collect::<Result<_, _>>()?;
+    Ok(Game { id, sets })
 }
 
-fn parse_set(encoded_set: &str) -> Set {
+fn parse_set(encoded_set: &str) -> Result<Set, Unexpected<'_>> {
     let cubes_extracted = encoded_set
         .split(',')
         .map(|x| x.trim())
         .map(parse_cube)
-        .collect::<HashMap<&str, u32>>();
-    Set {
+        .collect::<Result<HashMap<&str, u32>, _>>()?;
+    Ok(Set {
         red: *(cubes_extracted.get("red").unwrap_or(&0)),
         green: *(cubes_extracted.get("green").unwrap_or(&0)),
         blue: *(cubes_extracted.get("blue").unwrap_or(&0)),
+    })
+}
+
+fn parse_cube(encoded_cube_extraction: &str) -> Result<(&str, u32), Unexpected<'_>> {
+    let (quantity, color) = error::split_once(
+        encoded_cube_extraction,
+        " ",
+        "a quantity followed by a color",
+    )?;
+    if !["red", "green", "blue"].contains(&color) {
+        return Err(Unexpected::new(color, "red, green or blue"));
+    }
+    Ok((color, error::number(quantity)?))
+}
+
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+    let mut input = String::new();
+    let (mut possible, mut power) = (0, 0);
+    for id in 1..=size.max(1) as u64 {
+        let mut minimum = [0; 3];
+        let sets = (0..rng.between(1, 6))
+            .map(|_| {
+                let mut colors = [0, 1, 2];
+                rng.shuffle(&mut colors);
+                colors[..rng.between(1, 3) as usize]
+                    .iter()
+                    .map(|&color| {
+                        let quantity = rng.between(1, 20);
+                        minimum[color] = minimum[color].max(quantity);
+                        format!("{} {}", quantity, COLORS[color])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        writeln!(input, "Game {}: {}", id, sets).unwrap();
+        if minimum[0] <= 12 && minimum[1] <= 13 && minimum[2] <= 14 {
+            possible += id;
+        }
+        power += minimum.iter().product::<u64>();
+    }
+    Generated {
+        input,
+        expected: vec![(1, Answer::from(possible)), (2, Answer::from(power))],
     }
 }
 
-fn parse_cube(encoded_cube_extraction: &str) -> (&str, u32) {
-    let splitted_extraction = encoded_cube_extraction
-        .splitn(2, ' ')
-        .collect::<Vec<&str>>();
-    let quantity = splitted_extraction.first().unwrap();
-    let color = splitted_extraction.last().unwrap();
-    (color, quantity.parse::<u32>().unwrap())
+fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+    let malformed = |at: &str, expected| Unexpected::new(at, expected).locate(DAY, input);
+    let overflow = || ParseError::whole_input(DAY, "a sum of powers below 2^64");
+    let mut sum = 0_u64;
+    for line in input.trim_end().split('\n') {
+        let Some((header, sets)) = line.split_once(':') else {
+            return Err(malformed(line, "':'"));
+        };
+        let id = header
+            .strip_prefix("Game ")
+            .ok_or_else(|| malformed(header, "'Game '"))?;
+        let id = id.parse::<u32>().map_err(|_| malformed(id, "a number"))?;
+        let mut most = [0_u32; 3];
+        for set in sets.split(';') {
+            let mut counts = [0; 3];
+            for cubes in set.split(',').map(str::trim) {
+                let (quantity, color) = cubes
+                    .split_once(' ')
+                    .ok_or_else(|| malformed(cubes, "a quantity followed by a color"))?;
+                let color = COLORS
+                    .iter()
+                    .position(|&name| name == color)
+                    .ok_or_else(|| malformed(color, "red, green or blue"))?;
+                counts[color] = quantity
+                    .parse()
+                    .map_err(|_| malformed(quantity, "a number"))?;
+            }
+            for (most, count) in most.iter_mut().zip(counts) {
+                *most = (*most).max(count);
+            }
+        }
+        let [red, green, blue] = most.map(u64::from);
+        let value = if part == 1 {
+            if red <= 12 && green <= 13 && blue <= 14 {
+                u64::from(id)
+            } else {
+                0
+            }
+        } else {
+            red.checked_mul(green)
+                .and_then(|power| power.checked_mul(blue))
+                .ok_or_else(overflow)?
+        };
+        sum = sum.checked_add(value).ok_or_else(overflow)?;
+    }
+    Ok(Answer::from(sum))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers;
 
     #[test]
-    fn sample_inputs_part_1() {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        assert_eq!(part1(input), 8);
-    }
-
-    #[test]
-    fn sample_inputs_part_2() {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        assert_eq!(part2(input), 2286);
+    fn solutions() {
+        answers::assert_solution::<Day02>(DAY, 1);
+        answers::assert_solution::<Day02>(DAY, 2);
     }
 
     #[test]
-    fn solutions() {
-        let input = include_str!("../input/day02.txt");
-        assert_eq!(part1(input), 2617);
-        assert_eq!(part2(input), 59795);
+    fn malformed_game() {
+        let input = "Game 1: 3 blue, 4 red
+Game 2: 1 blue, two green";
+        let error = parse_games(input).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 17));
+        assert_eq!(error.expected, "a number");
+        assert_eq!(error.line_content, "Game 2: 1 blue, two green");
     }
 }
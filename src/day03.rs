@@ -1,4 +1,11 @@
-use std::collections::HashSet;
+use crate::error::{self, ParseError, Unexpected};
+use crate::generate::{Generated, Rng};
+use crate::minimize::Reduction;
+use crate::registry::Puzzle;
+use crate::{Answer, Solution};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
 
 #[derive(Debug)]
 struct Number {
@@ -13,75 +20,156 @@ struct Symbol {
     pos: isize,
 }
 
-pub fn main() {
-    let input = include_str!("../input/day03.txt");
-    println!("PART 1: {}", part1(input));
-    println!("PART 2: {}", part2(input));
+/// The engine schematic: the numbers and the symbols of the grid.
+///
+/// ```
+/// use advent_of_code::day03::{self, Schematic};
+///
+/// let schematic = "467..114..\n...*......\n..35..633.\n".parse::<Schematic>()?;
+/// assert_eq!(schematic.part_numbers().collect::<Vec<_>>(), [467, 35]);
+/// assert_eq!(schematic.gear_ratios().collect::<Vec<_>>(), [16345]);
+///
+/// let schematic = Schematic::new(vec![(0, 0, 467), (2, 2, 35)], vec![(1, 3, '*')]);
+/// assert_eq!(schematic.gear_ratios().collect::<Vec<_>>(), [16345]);
+/// assert_eq!(day03::solve_part1(&schematic)?, 502);
+/// assert_eq!(day03::solve_part2(&schematic)?, 16345);
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Debug)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
 }
 
-fn part1(input: &str) -> u32 {
-    let input = input.trim_end();
-    let numbers = parse_numbers(input);
-    let symbols = parse_symbols(input);
-    numbers
-        .iter()
-        .filter(|n| is_adjacent_to_symbol(n, &symbols))
-        .map(|n| n.value)
-        .sum()
+impl Schematic {
+    /// Numbers and symbols are given as their row, their (first) column and their value.
+    pub fn new(numbers: Vec<(usize, usize, u32)>, symbols: Vec<(usize, usize, char)>) -> Schematic {
+        let numbers = numbers
+            .into_iter()
+            .map(|(row, column, value)| {
+                let end = column + value.to_string().len() - 1;
+                Number {
+                    value,
+                    adjacent_positions: adjacent_position_to_number(
+                        row.try_into().unwrap(),
+                        column.try_into().unwrap(),
+                        end.try_into().unwrap(),
+                    ),
+                }
+            })
+            .collect();
+        let symbols = symbols
+            .into_iter()
+            .map(|(row, pos, value)| Symbol {
+                value,
+                row: row.try_into().unwrap(),
+                pos: pos.try_into().unwrap(),
+            })
+            .collect();
+        Schematic { numbers, symbols }
+    }
+
+    pub fn part_numbers(&self) -> impl Iterator<Item = u32> + '_ {
+        self.numbers
+            .iter()
+            .filter(|n| is_adjacent_to_symbol(n, &self.symbols))
+            .map(|n| n.value)
+    }
+
+    pub fn gear_ratios(&self) -> impl Iterator<Item = u64> + '_ {
+        self.symbols
+            .iter()
+            .filter(|s| s.value == '*')
+            .map(|s| calc_gear_ratio(s, &self.numbers))
+            .filter(|&ratio| ratio > 0)
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Schematic, ParseError> {
+        parse_schematic(input)
+    }
+}
+
+const DAY: u8 = 3;
+
+pub const PUZZLE: Puzzle = Puzzle::new::<Day03>(DAY, "Gear Ratios")
+    .with_generator(generate)
+    .with_reference(reference)
+    .with_reductions(&[Reduction::Lines, Reduction::Columns]);
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Schematic, ParseError> {
+        parse_schematic(input)
+    }
+
+    fn part1(schematic: &Schematic) -> Result<Answer, ParseError> {
+        solve_part1(schematic).map(Answer::from)
+    }
+
+    fn part2(schematic: &Schematic) -> Result<Answer, ParseError> {
+        solve_part2(schematic).map(Answer::from)
+    }
 }
 
-fn part2(input: &str) -> u32 {
+pub fn solve_part1(schematic: &Schematic) -> Result<u64, ParseError> {
+    Ok(schematic.part_numbers().map(u64::from).sum())
+}
+
+pub fn solve_part2(schematic: &Schematic) -> Result<u64, ParseError> {
+    schematic
+        .gear_ratios()
+        .try_fold(0_u64, |sum, ratio| sum.checked_add(ratio))
+        .ok_or_else(|| ParseError::whole_input(DAY, "a sum of gear ratios below 2^64"))
+}
+
+fn parse_schematic(input: &str) -> Result<Schematic, ParseError> {
     let input = input.trim_end();
-    let numbers = parse_numbers(input);
-    let symbols = parse_symbols(input);
-    symbols
-        .iter()
-        .filter(|s| s.value == '*')
-        .map(|s| calc_gear_ratio(s, &numbers))
-        .sum()
+    Ok(Schematic {
+        numbers: parse_numbers(input)?,
+        symbols: parse_symbols(input),
+    })
 }
 
-fn parse_numbers(input: &str) -> Vec<Number> {
+fn parse_numbers(input: &str) -> Result<Vec<Number>, ParseError> {
     input
         .split('\n')
         .enumerate()
-        .flat_map(|(row_number, row_content)| {
-            parse_row_numbers(row_content, row_number, Vec::new(), 0)
-        })
-        .collect::<Vec<_>>()
+        .map(|(row_number, row_content)| parse_row_numbers(row_content, row_number))
+        .flatten_ok()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.locate(DAY, input))
 }
 
-fn parse_row_numbers(
-    remaining_row: &str,
-    row_number: usize,
-    mut acc: Vec<Number>,
-    index: usize,
-) -> Vec<Number> {
-    if remaining_row.is_empty() {
-        acc
-    } else {
-        let current_char = remaining_row.chars().next().unwrap();
-        if !current_char.is_ascii_digit() {
-            parse_row_numbers(&remaining_row[1..], row_number, acc, index + 1)
-        } else {
-            let length = remaining_row
-                .chars()
-                .take_while(|c| c.is_ascii_digit())
-                .count();
-            let value = remaining_row[..length].parse::<u32>().unwrap();
-
-            let row: isize = row_number.try_into().unwrap();
-            let start: isize = index.try_into().unwrap();
-            let end: isize = (index + length - 1).try_into().unwrap();
-
-            let n = Number {
-                value,
-                adjacent_positions: adjacent_position_to_number(row, start, end),
-            };
-            acc.push(n);
-            parse_row_numbers(&remaining_row[length..], row_number, acc, index + length)
+fn parse_row_numbers(row: &str, row_number: usize) -> Result<Vec<Number>, Unexpected<'_>> {
+    let mut numbers = vec![];
+    let mut chars = row.char_indices().enumerate().peekable();
+    while let Some((index, (offset, c))) = chars.next() {
+        if !c.is_ascii_digit() {
+            continue;
+        }
+        let mut length = 1;
+        while chars.next_if(|(_, (_, c))| c.is_ascii_digit()).is_some() {
+            length += 1;
         }
+        let value = error::number(&row[offset..offset + length])?;
+
+        let row: isize = row_number.try_into().unwrap();
+        let start: isize = index.try_into().unwrap();
+        let end: isize = (index + length - 1).try_into().unwrap();
+
+        numbers.push(Number {
+            value,
+            adjacent_positions: adjacent_position_to_number(row, start, end),
+        });
     }
+    Ok(numbers)
 }
 
 fn adjacent_position_to_number(row: isize, start: isize, end: isize) -> HashSet<(isize, isize)> {
@@ -125,13 +213,13 @@ fn is_adjacent_to_symbol(n: &Number, symbols: &[Symbol]) -> bool {
     !adjacent_symbols.is_empty()
 }
 
-fn calc_gear_ratio(s: &Symbol, numbers: &[Number]) -> u32 {
+fn calc_gear_ratio(s: &Symbol, numbers: &[Number]) -> u64 {
     let adjacent_numbers = numbers
         .iter()
         .map(|n| (n, &n.adjacent_positions))
         .filter_map(|(n, positions)| {
             if positions.contains(&(s.row, s.pos)) {
-                Some(n.value)
+                Some(u64::from(n.value))
             } else {
                 None
             }
@@ -148,46 +236,151 @@ fn calc_gear_ratio(s: &Symbol, numbers: &[Number]) -> u32 {
     }
 }
 
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    const SYMBOLS: &[u8] = b"***#+$/@=%&";
+    let side = size.max(3);
+    let mut grid = vec![vec![b'.'; side]; side];
+    let mut numbers = vec![];
+    for (row, cells) in grid.iter_mut().enumerate() {
+        let mut column = 0;
+        while column < side {
+            if rng.chance(8) {
+                cells[column] = *rng.pick(SYMBOLS);
+                column += 1;
+                continue;
+            }
+            let value = rng.between(1, 999);
+            let digits = value.to_string();
+            let end = column + digits.len();
+            if rng.chance(15) && end <= side {
+                cells[column..end].copy_from_slice(digits.as_bytes());
+                numbers.push((row, column, end - 1, value));
+                column = end + 1;
+            } else {
+                column += 1;
+            }
+        }
+    }
+
+    let touches = |&(row, start, end, _): &(usize, usize, usize, u64), r: usize, c: usize| {
+        r + 1 >= row && r <= row + 1 && c + 1 >= start && c <= end + 1
+    };
+    let (mut part_numbers, mut gear_ratios) = (0, 0);
+    for number in &numbers {
+        let is_part = grid.iter().enumerate().any(|(r, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .any(|(c, &cell)| cell != b'.' && !cell.is_ascii_digit() && touches(number, r, c))
+        });
+        if is_part {
+            part_numbers += number.3;
+        }
+    }
+    for (r, cells) in grid.iter().enumerate() {
+        for (c, _) in cells.iter().enumerate().filter(|(_, &cell)| cell == b'*') {
+            let adjacent = numbers
+                .iter()
+                .filter(|number| touches(number, r, c))
+                .collect::<Vec<_>>();
+            if let [a, b] = adjacent[..] {
+                gear_ratios += a.3 * b.3;
+            }
+        }
+    }
+
+    let mut input = String::new();
+    for cells in &grid {
+        input.push_str(std::str::from_utf8(cells).unwrap());
+        input.push('\n');
+    }
+    Generated {
+        input,
+        expected: vec![
+            (1, Answer::from(part_numbers)),
+            (2, Answer::from(gear_ratios)),
+        ],
+    }
+}
+
+fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+    let input = input.trim_end();
+    let rows = input.split('\n').collect::<Vec<_>>();
+    let grid = rows
+        .iter()
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // Every run of digits as its row, first and last column, and value.
+    let mut numbers = vec![];
+    for (row, cells) in grid.iter().enumerate() {
+        let mut column = 0;
+        while column < cells.len() {
+            if !cells[column].is_ascii_digit() {
+                column += 1;
+                continue;
+            }
+            let start = column;
+            while column < cells.len() && cells[column].is_ascii_digit() {
+                column += 1;
+            }
+            let digits = cells[start..column].iter().collect::<String>();
+            let value = digits.parse::<u32>().map_err(|_| {
+                let offset = rows[row].char_indices().nth(start).map_or(0, |(i, _)| i);
+                Unexpected::new(&rows[row][offset..], "a number").locate(DAY, input)
+            })?;
+            numbers.push((row, start, column - 1, u64::from(value)));
+        }
+    }
+
+    let cell = |r: usize, c: usize| grid.get(r).and_then(|cells| cells.get(c)).copied();
+    let neighbours = |&(row, start, end, _): &(usize, usize, usize, u64)| {
+        (row.saturating_sub(1)..=row + 1)
+            .flat_map(move |r| (start.saturating_sub(1)..=end + 1).map(move |c| (r, c)))
+            .filter(move |&(r, c)| r != row || c < start || c > end)
+    };
+    let is_symbol = |c: char| !c.is_ascii_digit() && c != '.';
+
+    let mut sum = 0_u64;
+    if part == 1 {
+        for number in &numbers {
+            if neighbours(number).any(|(r, c)| cell(r, c).is_some_and(is_symbol)) {
+                sum += number.3;
+            }
+        }
+    } else {
+        let mut gears = HashMap::<(usize, usize), Vec<u64>>::new();
+        for number in &numbers {
+            for (r, c) in neighbours(number).filter(|&(r, c)| cell(r, c) == Some('*')) {
+                gears.entry((r, c)).or_default().push(number.3);
+            }
+        }
+        for values in gears.values() {
+            if let [a, b] = values[..] {
+                sum = sum.checked_add(a * b).ok_or_else(|| {
+                    ParseError::whole_input(DAY, "a sum of gear ratios below 2^64")
+                })?;
+            }
+        }
+    }
+    Ok(Answer::from(sum))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers;
 
     #[test]
-    fn sample_inputs_part_1() {
-        let input = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
-";
-        assert_eq!(part1(input), 4361);
-    }
-
-    #[test]
-    fn sample_inputs_part_2() {
-        let input = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..
-";
-        assert_eq!(part2(input), 467835);
+    fn solutions() {
+        answers::assert_solution::<Day03>(DAY, 1);
+        answers::assert_solution::<Day03>(DAY, 2);
     }
 
     #[test]
-    fn solutions() {
-        let input = include_str!("../input/day03.txt");
-        assert_eq!(part1(input), 530849);
-        assert_eq!(part2(input), 84900879);
+    fn number_too_large() {
+        let error = parse_schematic("467..114..\n..99999999999*.\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.expected, "a number");
     }
 }
@@ -1,94 +1,138 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt;
+use crate::error::{self, ParseError, Unexpected};
+use crate::generate::{Generated, Rng};
+use crate::registry::Puzzle;
+use crate::{Answer, Solution};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::str::FromStr;
 
-struct Card {
-    id: u32,
-    winning_numbers: Vec<u32>,
-    owned_numbers: Vec<u32>,
+/// A scratchcard with its winning numbers and the numbers it has.
+///
+/// ```
+/// use advent_of_code::day04::{self, Card};
+///
+/// let card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".parse::<Card>()?;
+/// assert_eq!(card.matches(), 4);
+/// assert_eq!(card.points(), Some(8));
+///
+/// let cards = vec![card, Card::new(2, vec![13, 32], vec![61, 30])];
+/// assert_eq!(day04::solve_part1(&cards)?, 8);
+/// assert_eq!(day04::solve_part2(&cards)?, 3);
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning_numbers: Vec<u32>,
+    pub owned_numbers: Vec<u32>,
 }
 
-impl fmt::Debug for Card {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "id: {}", self.id)
+impl Card {
+    pub fn new(id: u32, winning_numbers: Vec<u32>, owned_numbers: Vec<u32>) -> Card {
+        Card {
+            id,
+            winning_numbers,
+            owned_numbers,
+        }
+    }
+
+    pub fn matches(&self) -> usize {
+        count_winning_numbers(self)
+    }
+
+    pub fn points(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            count => 1_u64.checked_shl((count - 1).try_into().ok()?),
+        }
     }
 }
 
-pub fn main() {
-    let input = include_str!("../input/day04.txt");
-    println!("PART 1: {}", part1(input));
-    println!("PART 2: {}", part2(input));
+impl FromStr for Card {
+    type Err = ParseError;
+
+    fn from_str(raw_card: &str) -> Result<Card, ParseError> {
+        parse_card(raw_card).map_err(|e| e.locate(DAY, raw_card))
+    }
 }
 
-fn part1(input: &str) -> usize {
-    let cards = input.trim_end().split('\n').map(parse_card);
+const DAY: u8 = 4;
+
+pub const PUZZLE: Puzzle = Puzzle::new::<Day04>(DAY, "Scratchcards")
+    .with_generator(generate)
+    .with_reference(reference);
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
+        parse_cards(input)
+    }
+
+    fn part1(cards: &Vec<Card>) -> Result<Answer, ParseError> {
+        solve_part1(cards).map(Answer::from)
+    }
+
+    fn part2(cards: &Vec<Card>) -> Result<Answer, ParseError> {
+        solve_part2(cards).map(Answer::from)
+    }
+}
+
+pub fn solve_part1(cards: &[Card]) -> Result<u64, ParseError> {
     cards
-        .map(|card| {
-            let count = count_winning_numbers(&card);
-            if count == 0 {
-                0
-            } else {
-                2_usize.pow((count - 1).try_into().unwrap())
-            }
-        })
-        .sum()
+        .iter()
+        .map(Card::points)
+        .try_fold(0_u64, |sum, points| sum.checked_add(points?))
+        .ok_or_else(|| ParseError::whole_input(DAY, "points below 2^64"))
 }
 
-fn part2(input: &str) -> u32 {
-    let cards = input
-        .trim_end()
-        .split('\n')
-        .map(parse_card)
-        .map(|card| (card.id, card))
-        .collect::<HashMap<_, _>>();
-
-    fn all_cards(
-        mut cards_frequencies: HashMap<u32, u32>,
-        current_card: u32,
-        map_cards: &HashMap<u32, Card>,
-    ) -> HashMap<u32, u32> {
-        if map_cards.get(&current_card).is_none() {
-            cards_frequencies
-        } else {
-            let card = map_cards.get(&current_card).unwrap();
-            let count_winning_numbers = count_winning_numbers(card);
-            if count_winning_numbers > 0 {
-                let count: u32 = count_winning_numbers.try_into().unwrap();
-                let new_cards_range = (card.id + 1)..=(card.id + count);
-                let &&current_card_frequence = &cards_frequencies.get(&current_card).unwrap();
-                for id in new_cards_range {
-                    *cards_frequencies.get_mut(&id).unwrap() += current_card_frequence;
-                }
-                all_cards(cards_frequencies, current_card + 1, map_cards)
-            } else {
-                all_cards(cards_frequencies, current_card + 1, map_cards)
-            }
+pub fn solve_part2(cards: &[Card]) -> Result<u64, ParseError> {
+    let overflow = || ParseError::whole_input(DAY, "a number of cards below 2^64");
+    let mut copies = vec![1_u64; cards.len()];
+    for (position, card) in cards.iter().enumerate() {
+        let won = position + 1..(position + 1 + card.matches()).min(cards.len());
+        for copy in won {
+            copies[copy] = copies[copy]
+                .checked_add(copies[position])
+                .ok_or_else(overflow)?;
         }
     }
+    copies
+        .iter()
+        .try_fold(0_u64, |sum, &copies| sum.checked_add(copies))
+        .ok_or_else(overflow)
+}
 
-    let frequencies = cards
-        .keys()
-        .map(|&id| (id, 1))
-        .collect::<HashMap<u32, u32>>();
-    all_cards(frequencies, 1, &cards).values().sum()
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, ParseError> {
+    input
+        .trim_end()
+        .split('\n')
+        .map(parse_card)
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.locate(DAY, input))
 }
 
-fn parse_card(raw_card: &str) -> Card {
-    let (header, card_numbers) = raw_card.split_once(": ").unwrap();
+fn parse_card(raw_card: &str) -> Result<Card, Unexpected<'_>> {
+    let (header, card_numbers) = error::split_once(raw_card, ": ", "': '")?;
 
-    let (raw_winning_numbers, raw_owned_numbers) = card_numbers.split_once('|').unwrap();
+    let (raw_winning_numbers, raw_owned_numbers) = error::split_once(card_numbers, "|", "'|'")?;
 
-    let id = header.replace("Card", "").trim().parse().unwrap();
-    Card {
-        id,
+    let id = header
+        .strip_prefix("Card")
+        .ok_or_else(|| Unexpected::new(header, "'Card'"))?;
+    Ok(Card {
+        id: error::number(id.trim())?,
         winning_numbers: raw_winning_numbers
             .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect(),
+            .map(error::number)
+            .collect::<Result<_, _>>()?,
         owned_numbers: raw_owned_numbers
             .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect(),
-    }
+            .map(error::number)
+            .collect::<Result<_, _>>()?,
+    })
 }
 
 fn count_winning_numbers(card: &Card) -> usize {
@@ -97,36 +141,154 @@ fn count_winning_numbers(card: &Card) -> usize {
     w_set.intersection(&o_set).count()
 }
 
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let cards = size.max(1);
+    let width = cards.to_string().len();
+    let numbers = |numbers: &[u64]| {
+        numbers
+            .iter()
+            .map(|n| format!("{:>2}", n))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut input = String::new();
+    let mut points = 0;
+    let mut copies = vec![1; cards];
+    for card in 0..cards {
+        let mut pool = (1..=99).collect::<Vec<u64>>();
+        rng.shuffle(&mut pool);
+        let matches = if rng.chance(30) { rng.between(1, 4) } else { 0 };
+        let matches = (matches as usize).min(cards - card - 1);
+        let mut owned = pool[..matches]
+            .iter()
+            .chain(&pool[10..35 - matches])
+            .copied()
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut owned);
+        writeln!(
+            input,
+            "Card {:>width$}: {} | {}",
+            card + 1,
+            numbers(&pool[..10]),
+            numbers(&owned)
+        )
+        .unwrap();
+
+        if matches > 0 {
+            points += 1 << (matches - 1);
+        }
+        for won in card + 1..=card + matches {
+            copies[won] += copies[card];
+        }
+    }
+    Generated {
+        input,
+        expected: vec![
+            (1, Answer::from(points as u64)),
+            (2, Answer::from(copies.iter().sum::<u64>())),
+        ],
+    }
+}
+
+fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+    let malformed = |at: &str, expected| Unexpected::new(at, expected).locate(DAY, input);
+    let numbers = |raw: &str| {
+        raw.split_whitespace()
+            .map(|n| n.parse::<u32>().map_err(|_| malformed(n, "a number")))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let mut matches = vec![];
+    for line in input.trim_end().split('\n') {
+        let Some((header, card)) = line.split_once(": ") else {
+            return Err(malformed(&line[line.len()..], "': '"));
+        };
+        let Some((winning, owned)) = card.split_once('|') else {
+            return Err(malformed(&card[card.len()..], "'|'"));
+        };
+        let id = header
+            .strip_prefix("Card")
+            .ok_or_else(|| malformed(header, "'Card'"))?
+            .trim();
+        id.parse::<u32>().map_err(|_| malformed(id, "a number"))?;
+        let winning = numbers(winning)?;
+        let owned = numbers(owned)?;
+        matches.push(owned.iter().filter(|n| winning.contains(n)).count());
+    }
+
+    if part == 1 {
+        let mut points = 0_u64;
+        for &m in &matches {
+            let worth = match m {
+                0 => Some(0),
+                m => u32::try_from(m - 1)
+                    .ok()
+                    .and_then(|shift| 1_u64.checked_shl(shift)),
+            };
+            points = worth
+                .and_then(|worth| points.checked_add(worth))
+                .ok_or_else(|| ParseError::whole_input(DAY, "points below 2^64"))?;
+        }
+        return Ok(Answer::from(points));
+    }
+    let mut pending = (0..matches.len()).collect::<Vec<_>>();
+    let mut scratched = 0_u64;
+    while let Some(card) = pending.pop() {
+        scratched = scratched
+            .checked_add(1)
+            .ok_or_else(|| ParseError::whole_input(DAY, "a number of cards below 2^64"))?;
+        pending.extend((card + 1..=card + matches[card]).filter(|&won| won < matches.len()));
+    }
+    Ok(Answer::from(scratched))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers;
 
     #[test]
-    fn sample_inputs_part_1() {
-        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(part1(input), 13);
+    fn solutions() {
+        answers::assert_solution::<Day04>(DAY, 1);
+        answers::assert_solution::<Day04>(DAY, 2);
     }
 
     #[test]
-    fn sample_inputs_part_2() {
-        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(part2(input), 30);
+    fn card_without_separator() {
+        let error = parse_cards("Card 1: 41 48 83 86 17 | 83 86  6 31\nCard 2: 13 32 20 16 61")
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 23));
+        assert_eq!(error.expected, "'|'");
     }
 
     #[test]
-    fn solutions() {
-        let input = include_str!("../input/day04.txt");
-        assert_eq!(part1(input), 26914);
-        assert_eq!(part2(input), 13080971);
+    fn too_many_points() {
+        let numbers = (1..=65)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let input = format!("Card 1: {} | {}\n", numbers, numbers);
+        let error = ParseError::whole_input(DAY, "points below 2^64");
+        assert_eq!(
+            Day04::parse(&input).and_then(|cards| Day04::part1(&cards)),
+            Err(error.clone())
+        );
+        assert_eq!(reference(&input, 1), Err(error));
+    }
+
+    #[test]
+    fn many_cards() {
+        let cards = (1..=300_000)
+            .map(|id| Card::new(id, vec![1], vec![1]))
+            .collect::<Vec<_>>();
+        assert_eq!(solve_part2(&cards), Ok(300_000 * 300_001 / 2));
+    }
+
+    #[test]
+    fn copies_follow_card_positions() {
+        let input = "Card 5: 1 2 | 1 2\nCard 5: 3 | 3\nCard 9: 4 | 5\n";
+        let cards = parse_cards(input).unwrap();
+        assert_eq!(solve_part2(&cards), Ok(7));
+        assert_eq!(reference(input, 2), Ok(Answer::from(7_u64)));
     }
 }
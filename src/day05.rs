@@ -1,16 +1,101 @@
-struct Map {
-    conversions: Vec<Conversion>,
+use crate::error::{self, ParseError, Unexpected};
+use crate::generate::{Generated, Rng};
+use crate::minimize::Reduction;
+use crate::registry::Puzzle;
+use crate::{Answer, Solution};
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+/// The seeds to plant and the maps leading from seeds to locations.
+///
+/// ```
+/// use advent_of_code::day05::{self, Almanac, Conversion, Map, Range};
+///
+/// let almanac = "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n"
+///     .parse::<Almanac>()?;
+/// assert_eq!(day05::solve_part1(&almanac)?, 13);
+/// assert_eq!(day05::solve_part2(&almanac)?, 57);
+///
+/// let map = Map::new(vec![Conversion::new(52, 50, 48).unwrap()]);
+/// assert_eq!(map.convert(79), 81);
+/// assert_eq!("seed-to-soil map:\n52 50 48\n".parse::<Map>()?, map);
+/// assert_eq!(
+///     map.convert_range(Range::new(40, 60)),
+///     [Range::new(52, 62), Range::new(40, 50)]
+/// );
+/// let almanac = Almanac::new(vec![79, 14], vec![map]);
+/// assert_eq!(day05::solve_part2(&almanac)?, 81);
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    seed_ranges: Result<Vec<Range>, ParseError>,
+    maps: Vec<Map>,
+}
+
+impl Almanac {
+    pub fn new(seeds: Vec<u64>, maps: Vec<Map>) -> Almanac {
+        let seed_ranges = seeds
+            .chunks(2)
+            .map(|pair| match *pair {
+                [from, length] => from
+                    .checked_add(length)
+                    .map(|to| Range { from, to })
+                    .ok_or_else(|| ParseError::whole_input(DAY, "a range ending below 2^64")),
+                _ => Err(ParseError::whole_input(DAY, "a seed range length")),
+            })
+            .collect();
+        Almanac {
+            seeds,
+            seed_ranges,
+            maps,
+        }
+    }
+
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    pub fn seed_ranges(&self) -> Result<&[Range], ParseError> {
+        self.seed_ranges.as_deref().map_err(ParseError::clone)
+    }
+
+    pub fn maps(&self) -> &[Map] {
+        &self.maps
+    }
+
+    pub fn location(&self, seed: u64) -> u64 {
+        self.maps.iter().fold(seed, |value, map| map.convert(value))
+    }
+}
+
+impl FromStr for Almanac {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Almanac, ParseError> {
+        parse_almanac(input)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Map {
+    pub conversions: Vec<Conversion>,
 }
 
 impl Map {
-    fn convert(&self, raw: u64) -> u64 {
+    pub fn new(conversions: Vec<Conversion>) -> Map {
+        Map { conversions }
+    }
+
+    pub fn convert(&self, raw: u64) -> u64 {
         self.conversions
             .iter()
             .find_map(|conversion| conversion.convert(raw))
             .unwrap_or(raw)
     }
 
-    fn convert_range(&self, range: Range) -> Vec<Range> {
+    pub fn convert_range(&self, range: Range) -> Vec<Range> {
         let mut converted_ranges = vec![];
         let mut remaining = vec![range];
         for conversion in self.conversions.iter().as_slice() {
@@ -32,27 +117,64 @@ impl Map {
             remaining = new_remaining;
         }
         converted_ranges.append(&mut remaining);
+        converted_ranges.retain(|r| r.from < r.to);
         converted_ranges
     }
 }
 
+impl FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(raw_map: &str) -> Result<Map, ParseError> {
+        parse_map(raw_map.trim_end()).map_err(|e| e.locate(DAY, raw_map))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Range {
-    from: u64,
-    to: u64,
+pub struct Range {
+    pub from: u64,
+    pub to: u64,
 }
 
-#[derive(Debug)]
-struct Conversion {
+impl Range {
+    pub fn new(from: u64, to: u64) -> Range {
+        Range { from, to }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Conversion {
     source: u64,
     destination: u64,
     range: u64,
 }
 
 impl Conversion {
-    fn convert(&self, n: u64) -> Option<u64> {
+    pub fn new(destination: u64, source: u64, length: u64) -> Option<Conversion> {
+        source.checked_add(length)?;
+        destination.checked_add(length)?;
+        Some(Conversion {
+            source,
+            destination,
+            range: length,
+        })
+    }
+
+    pub fn destination(&self) -> u64 {
+        self.destination
+    }
+
+    pub fn source(&self) -> u64 {
+        self.source
+    }
+
+    pub fn length(&self) -> u64 {
+        self.range
+    }
+
+    pub fn convert(&self, n: u64) -> Option<u64> {
         if self.source <= n && n < self.source + self.range {
-            Some(self.destination + n - self.source)
+            Some(self.destination + (n - self.source))
         } else {
             None
         }
@@ -142,178 +264,318 @@ impl Conversion {
     }
 }
 
-pub fn main() {
-    let input = include_str!("../input/day05.txt");
-    println!("PART 1: {}", part1(input));
-    println!("PART 2: {}", part2(input));
+const DAY: u8 = 5;
+
+pub const PUZZLE: Puzzle = Puzzle::new::<Day05>(DAY, "If You Give A Seed A Fertilizer")
+    .with_generator(generate)
+    .with_reference(reference)
+    .with_reductions(&[Reduction::Blocks, Reduction::Lines]);
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Almanac;
+
+    fn parse(input: &str) -> Result<Almanac, ParseError> {
+        parse_almanac(input)
+    }
+
+    fn part1(almanac: &Almanac) -> Result<Answer, ParseError> {
+        solve_part1(almanac).map(Answer::from)
+    }
+
+    fn part2(almanac: &Almanac) -> Result<Answer, ParseError> {
+        solve_part2(almanac).map(Answer::from)
+    }
 }
 
-fn part1(input: &str) -> u64 {
-    let (seeds, maps) = parse_input(input);
-    seeds
+pub fn solve_part1(almanac: &Almanac) -> Result<u64, ParseError> {
+    almanac
+        .seeds
         .iter()
-        .map(|&seed| {
-            maps.iter()
-                .fold(seed, |current_state, map| map.convert(current_state))
-        })
+        .map(|&seed| almanac.location(seed))
         .min()
-        .unwrap()
+        .ok_or_else(|| ParseError::whole_input(DAY, "at least one seed"))
 }
 
-fn part2(input: &str) -> u64 {
-    let (ranged_seeds, maps) = parse_input2(input);
-    ranged_seeds
+pub fn solve_part2(almanac: &Almanac) -> Result<u64, ParseError> {
+    almanac
+        .seed_ranges()?
         .iter()
         .flat_map(|&range| {
-            maps.iter().fold(vec![range], |ranges, map| {
+            almanac.maps.iter().fold(vec![range], |ranges, map| {
                 ranges.iter().flat_map(|r| map.convert_range(*r)).collect()
             })
         })
         .map(|r| r.from)
         .min()
-        .unwrap()
+        .ok_or_else(|| ParseError::whole_input(DAY, "at least one non-empty seed range"))
 }
 
-fn parse_input(input: &str) -> (Vec<u64>, Vec<Map>) {
-    let (raw_seeds, raw_maps) = input.trim().split_once("\n\n").unwrap();
-    (parse_seeds(raw_seeds), parse_maps(raw_maps))
+fn parse_almanac(input: &str) -> Result<Almanac, ParseError> {
+    let locate = |e: Unexpected<'_>| e.locate(DAY, input);
+    let (raw_seeds, raw_maps) = split_sections(input).map_err(locate)?;
+    Ok(Almanac {
+        seeds: parse_seeds(raw_seeds).map_err(locate)?,
+        seed_ranges: parse_seeds2(raw_seeds).map_err(locate),
+        maps: parse_maps(raw_maps).map_err(locate)?,
+    })
 }
 
-fn parse_input2(input: &str) -> (Vec<Range>, Vec<Map>) {
-    let (raw_seeds, raw_maps) = input.trim().split_once("\n\n").unwrap();
-    (parse_seeds2(raw_seeds), parse_maps(raw_maps))
+fn split_sections(input: &str) -> Result<(&str, &str), Unexpected<'_>> {
+    error::split_once(input.trim(), "\n\n", "a blank line after the seeds")
 }
 
-fn parse_seeds(raw_seeds: &str) -> Vec<u64> {
-    raw_seeds
-        .replace("seeds: ", "")
+fn seed_numbers(raw_seeds: &str) -> Result<Vec<&str>, Unexpected<'_>> {
+    let raw_numbers = raw_seeds
+        .strip_prefix("seeds:")
+        .ok_or_else(|| Unexpected::new(raw_seeds, "'seeds:'"))?
         .split_whitespace()
-        .map(|raw_n| raw_n.parse().unwrap())
+        .collect::<Vec<_>>();
+    if raw_numbers.is_empty() {
+        Err(Unexpected::end_of(raw_seeds, "at least one seed"))
+    } else {
+        Ok(raw_numbers)
+    }
+}
+
+fn parse_seeds(raw_seeds: &str) -> Result<Vec<u64>, Unexpected<'_>> {
+    seed_numbers(raw_seeds)?
+        .into_iter()
+        .map(error::number)
         .collect()
 }
 
-fn parse_seeds2(raw_seeds: &str) -> Vec<Range> {
-    let raw_seeds = raw_seeds.replace("seeds: ", "");
-    let raw_numbers = raw_seeds.split_whitespace().collect::<Vec<_>>();
+fn parse_seeds2(raw_seeds: &str) -> Result<Vec<Range>, Unexpected<'_>> {
+    let raw_numbers = seed_numbers(raw_seeds)?;
 
     raw_numbers
         .chunks(2)
         .map(|l| {
-            let from = l[0].parse::<u64>().unwrap();
-            let length = l[1].parse::<u64>().unwrap();
-            Range {
+            let from = error::number::<u64>(l[0])?;
+            let raw_length = l
+                .get(1)
+                .ok_or_else(|| Unexpected::end_of(raw_seeds, "a seed range length"))?;
+            let length = error::number::<u64>(raw_length)?;
+            Ok(Range {
                 from,
-                to: from + length,
-            }
+                to: from
+                    .checked_add(length)
+                    .ok_or_else(|| Unexpected::new(raw_length, "a range ending below 2^64"))?,
+            })
         })
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, _>>()
 }
 
-fn parse_maps(raw_maps: &str) -> Vec<Map> {
+fn parse_maps(raw_maps: &str) -> Result<Vec<Map>, Unexpected<'_>> {
     raw_maps.split("\n\n").map(parse_map).collect()
 }
 
-fn parse_map(raw_map: &str) -> Map {
-    let (_header, maps) = raw_map.split_once('\n').unwrap();
-    let conversions = maps.split('\n').map(parse_conversion).collect();
-    Map { conversions }
+fn parse_map(raw_map: &str) -> Result<Map, Unexpected<'_>> {
+    let (_header, maps) = error::split_once(raw_map, "\n", "conversions after the map header")?;
+    let conversions = maps
+        .split('\n')
+        .map(parse_conversion)
+        .collect::<Result<_, _>>()?;
+    Ok(Map { conversions })
+}
+
+fn parse_conversion(raw_conversion: &str) -> Result<Conversion, Unexpected<'_>> {
+    let mut splitted_conversion = raw_conversion.split_whitespace();
+    let mut next_number = |expected| {
+        splitted_conversion
+            .next()
+            .ok_or_else(|| Unexpected::end_of(raw_conversion, expected))
+            .and_then(error::number)
+    };
+    let destination = next_number("a destination range start")?;
+    let source = next_number("a source range start")?;
+    let raw_range = splitted_conversion
+        .next()
+        .ok_or_else(|| Unexpected::end_of(raw_conversion, "a range length"))?;
+    Conversion::new(destination, source, error::number(raw_range)?)
+        .ok_or_else(|| Unexpected::new(raw_range, "a range ending below 2^64"))
+}
+
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let conversions = size.max(1) as u64;
+    let domain = 100 * conversions;
+    let seeds = (0..10)
+        .map(|_| (rng.below(domain), rng.between(1, 100)))
+        .collect::<Vec<_>>();
+
+    let mut input = String::from("seeds:");
+    for (start, length) in &seeds {
+        write!(input, " {} {}", start, length).unwrap();
+    }
+    input.push('\n');
+    let mut maps = vec![];
+    for pair in CATEGORIES.windows(2) {
+        let mut map = (0..conversions)
+            .map(|segment| {
+                let source = segment * 100 + rng.below(50);
+                (rng.below(domain), source, rng.between(1, 50))
+            })
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut map);
+        write!(input, "\n{}-to-{} map:\n", pair[0], pair[1]).unwrap();
+        for (destination, source, length) in &map {
+            writeln!(input, "{} {} {}", destination, source, length).unwrap();
+        }
+        maps.push(map);
+    }
+
+    let location = |seed: u64| {
+        maps.iter().fold(seed, |value, map| {
+            map.iter()
+                .find(|&&(_, source, length)| (source..source + length).contains(&value))
+                .map_or(value, |&(destination, source, _)| {
+                    destination + value - source
+                })
+        })
+    };
+    let part1 = seeds
+        .iter()
+        .flat_map(|&(start, length)| [start, length])
+        .map(location)
+        .min()
+        .unwrap();
+    let part2 = seeds
+        .iter()
+        .flat_map(|&(start, length)| start..start + length)
+        .map(location)
+        .min()
+        .unwrap();
+    Generated {
+        input,
+        expected: vec![(1, Answer::from(part1)), (2, Answer::from(part2))],
+    }
 }
 
-fn parse_conversion(raw_conversion: &str) -> Conversion {
-    let splitted_conversion = raw_conversion.split_whitespace().collect::<Vec<_>>();
-    Conversion {
-        source: splitted_conversion[1].parse().unwrap(),
-        destination: splitted_conversion[0].parse().unwrap(),
-        range: splitted_conversion[2].parse().unwrap(),
+fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+    let malformed = |at: &str, expected| Unexpected::new(at, expected).locate(DAY, input);
+    let end_of = |raw: &str, expected| malformed(&raw[raw.len()..], expected);
+    let number = |raw: &str| raw.parse::<u64>().map_err(|_| malformed(raw, "a number"));
+
+    let input = input.trim();
+    let Some((seeds, maps)) = input.split_once("\n\n") else {
+        return Err(end_of(input, "a blank line after the seeds"));
+    };
+    let seeds = seeds
+        .strip_prefix("seeds:")
+        .ok_or_else(|| malformed(seeds, "'seeds:'"))?
+        .split_whitespace()
+        .map(number)
+        .collect::<Result<Vec<_>, _>>()?;
+    if seeds.is_empty() {
+        return Err(end_of(
+            input.split('\n').next().unwrap_or(input),
+            "at least one seed",
+        ));
     }
+    let mut conversions = vec![];
+    for block in maps.split("\n\n") {
+        let Some((_header, lines)) = block.split_once('\n') else {
+            return Err(end_of(block, "conversions after the map header"));
+        };
+        let mut map = vec![];
+        for line in lines.split('\n') {
+            let numbers = line
+                .split_whitespace()
+                .take(3)
+                .map(number)
+                .collect::<Result<Vec<_>, _>>()?;
+            let [destination, source, length] = numbers[..] else {
+                return Err(end_of(line, "a destination, a source and a range length"));
+            };
+            if source.checked_add(length).is_none() || destination.checked_add(length).is_none() {
+                return Err(malformed(line, "a range ending below 2^64"));
+            }
+            map.push((destination, source, length));
+        }
+        conversions.push(map);
+    }
+
+    let location = |seed: u64| {
+        conversions.iter().fold(seed, |value, map| {
+            map.iter()
+                .find(|&&(_, source, length)| source <= value && value - source < length)
+                .map_or(value, |&(destination, source, _)| {
+                    destination + (value - source)
+                })
+        })
+    };
+    let lowest = if part == 1 {
+        seeds.iter().map(|&seed| location(seed)).min()
+    } else {
+        let mut lowest = None;
+        for pair in seeds.chunks(2) {
+            let [from, length] = *pair else {
+                return Err(ParseError::whole_input(DAY, "a seed range length"));
+            };
+            let to = from
+                .checked_add(length)
+                .ok_or_else(|| ParseError::whole_input(DAY, "a range ending below 2^64"))?;
+            lowest = (from..to).map(location).chain(lowest).min();
+        }
+        lowest
+    };
+    let lowest = lowest.ok_or_else(|| {
+        let expected = match part {
+            1 => "at least one seed",
+            _ => "at least one non-empty seed range",
+        };
+        ParseError::whole_input(DAY, expected)
+    })?;
+    Ok(Answer::from(lowest))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers;
 
     #[test]
-    fn sample_inputs_part_1() {
-        let input = "seeds: 79 14 55 13
-
-seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
-";
-        assert_eq!(part1(input), 35);
+    fn solutions() {
+        answers::assert_solution::<Day05>(DAY, 1);
+        answers::assert_solution::<Day05>(DAY, 2);
     }
 
     #[test]
-    fn sample_inputs_part_2() {
+    fn incomplete_conversion() {
         let input = "seeds: 79 14 55 13
 
 seed-to-soil map:
 50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
-
-temperature-to-humidity map:
-0 69 1
-1 0 69
-
-humidity-to-location map:
-60 56 37
-56 93 4
+52 50
 ";
-        assert_eq!(part2(input), 46);
+        let error = parse_almanac(input).unwrap_err();
+        assert_eq!((error.line, error.column), (5, 6));
+        assert_eq!(error.expected, "a range length");
     }
 
     #[test]
-    fn solutions() {
-        let input = include_str!("../input/day05.txt");
-        assert_eq!(part1(input), 199602917);
-        assert_eq!(part2(input), 2254686);
+    fn odd_seed_count_only_fails_part2() {
+        let input = "seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n";
+        let almanac = parse_almanac(input).unwrap();
+        assert_eq!(solve_part1(&almanac), Ok(14));
+        let error = solve_part2(&almanac).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 16));
+        assert_eq!(error.expected, "a seed range length");
+        assert_eq!(reference(input, 1), Ok(Answer::from(14_u64)));
+        assert_eq!(
+            reference(input, 2).map_err(|e| e.expected),
+            Err("a seed range length".to_string())
+        );
     }
 }
@@ -1,114 +1,306 @@
-struct Race {
-    time: u64,
-    best_distance: u64,
+use crate::error::{self, ParseError, Unexpected};
+use crate::generate::{Generated, Rng};
+use crate::registry::Puzzle;
+use crate::{Answer, Solution};
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub best_distance: u64,
+}
+
+impl Race {
+    pub fn new(time: u64, best_distance: u64) -> Race {
+        Race {
+            time,
+            best_distance,
+        }
+    }
+
+    pub fn ways_to_win(&self) -> u64 {
+        count_number_of_ways_to_win(self)
+    }
+}
+
+impl FromStr for Race {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Race, ParseError> {
+        parse_race(input).map_err(|e| e.locate(DAY, input))
+    }
+}
+
+/// The races of the sheet, and the single race read with bad kerning.
+///
+/// ```
+/// use advent_of_code::day06::{self, Race, Races};
+///
+/// let races = "Time:      7  15   30\nDistance:  9  40  200\n".parse::<Races>()?;
+/// assert_eq!(races.races()[0], Race::new(7, 9));
+/// assert_eq!(races.races()[0].ways_to_win(), 4);
+/// assert_eq!("Time: 7\nDistance: 9\n".parse::<Race>()?, Race::new(7, 9));
+/// assert_eq!(races.kerned()?, &Race::new(71530, 940200));
+/// assert_eq!(day06::solve_part1(&races)?, 288);
+/// assert_eq!(day06::solve_part2(&races)?, 71503);
+///
+/// let races = Races::new(vec![Race::new(7, 9), Race::new(15, 40)]);
+/// assert_eq!(races.kerned()?, &Race::new(715, 940));
+/// # Ok::<(), advent_of_code::error::ParseError>(())
+/// ```
+#[derive(Debug)]
+pub struct Races {
+    races: Vec<Race>,
+    kerned: Result<Race, ParseError>,
+}
+
+impl Races {
+    pub fn new(races: Vec<Race>) -> Races {
+        let kern = |number: fn(&Race) -> u64| {
+            races
+                .iter()
+                .map(|race| number(race).to_string())
+                .collect::<String>()
+                .parse::<u64>()
+                .ok()
+        };
+        let kerned = kern(|race| race.time)
+            .zip(kern(|race| race.best_distance))
+            .map(|(time, best_distance)| Race::new(time, best_distance))
+            .ok_or_else(|| ParseError::whole_input(DAY, "a kerned race below 2^64"));
+        Races { races, kerned }
+    }
+
+    pub fn races(&self) -> &[Race] {
+        &self.races
+    }
+
+    pub fn kerned(&self) -> Result<&Race, ParseError> {
+        self.kerned.as_ref().map_err(ParseError::clone)
+    }
 }
 
-pub fn main() {
-    let input = include_str!("../input/day06.txt");
-    println!("PART 1: {}", part1(input));
-    println!("PART 2: {}", part2(input));
+impl FromStr for Races {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Races, ParseError> {
+        parse_races(input)
+    }
 }
 
-fn part1(input: &str) -> u64 {
-    parse_input(input)
+const DAY: u8 = 6;
+
+pub const PUZZLE: Puzzle = Puzzle::new::<Day06>(DAY, "Wait For It")
+    .with_generator(generate)
+    .with_reference(reference);
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Races;
+
+    fn parse(input: &str) -> Result<Races, ParseError> {
+        parse_races(input)
+    }
+
+    fn part1(races: &Races) -> Result<Answer, ParseError> {
+        solve_part1(races).map(Answer::from)
+    }
+
+    fn part2(races: &Races) -> Result<Answer, ParseError> {
+        solve_part2(races).map(Answer::from)
+    }
+}
+
+pub fn solve_part1(races: &Races) -> Result<u64, ParseError> {
+    races
+        .races
         .iter()
-        .map(count_number_of_ways_to_win)
-        .reduce(|w1, w2| w1 * w2)
-        .unwrap()
+        .map(Race::ways_to_win)
+        .try_fold(1_u64, |product, ways| product.checked_mul(ways))
+        .ok_or_else(|| ParseError::whole_input(DAY, "a product of ways to win below 2^64"))
 }
 
-fn part2(input: &str) -> u64 {
-    count_number_of_ways_to_win(&parse_input2(input))
+pub fn solve_part2(races: &Races) -> Result<u64, ParseError> {
+    Ok(races.kerned()?.ways_to_win())
 }
 
-fn parse_input(input: &str) -> Vec<Race> {
-    let (time_line, distance_line) = input.trim().split_once('\n').unwrap();
-    let times = parse_times(time_line);
-    let distances = parse_distances(distance_line);
-    times
+fn parse_races(input: &str) -> Result<Races, ParseError> {
+    let locate = |e: Unexpected<'_>| e.locate(DAY, input);
+    Ok(Races {
+        races: parse_input(input).map_err(locate)?,
+        kerned: parse_input2(input).map_err(locate),
+    })
+}
+
+fn parse_input(input: &str) -> Result<Vec<Race>, Unexpected<'_>> {
+    let (time_line, distance_line) = error::split_once(input.trim(), "\n", "a distance line")?;
+    let times = parse_times(time_line)?;
+    let distances = parse_distances(distance_line)?;
+    if times.len() != distances.len() {
+        return Err(Unexpected::end_of(distance_line, "one distance per time"));
+    }
+    Ok(times
         .iter()
         .zip(distances)
         .map(|(&time, best_distance)| Race {
             time,
             best_distance,
         })
-        .collect()
+        .collect())
 }
 
-fn parse_input2(input: &str) -> Race {
-    let (time_line, distance_line) = input.trim().split_once('\n').unwrap();
-    let time = time_line
-        .chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect::<String>()
-        .parse()
-        .unwrap();
-    let best_distance = distance_line
+fn parse_race(input: &str) -> Result<Race, Unexpected<'_>> {
+    match parse_input(input)?[..] {
+        [race] => Ok(race),
+        _ => Err(Unexpected::new(input.trim(), "a single race")),
+    }
+}
+
+fn parse_input2(input: &str) -> Result<Race, Unexpected<'_>> {
+    let (time_line, distance_line) = error::split_once(input.trim(), "\n", "a distance line")?;
+    let time = parse_kerned_number(time_line, "Time:")?;
+    let best_distance = parse_kerned_number(distance_line, "Distance:")?;
+    Ok(Race {
+        time,
+        best_distance,
+    })
+}
+
+fn parse_kerned_number<'a>(line: &'a str, header: &'static str) -> Result<u64, Unexpected<'a>> {
+    let raw_number = strip_header(line, header)?;
+    raw_number
         .chars()
         .filter(|c| c.is_ascii_digit())
         .collect::<String>()
         .parse()
-        .unwrap();
-    Race {
-        time,
-        best_distance,
-    }
+        .map_err(|_| Unexpected::new(raw_number.trim_start(), "a number"))
 }
 
-fn parse_times(line: &str) -> Vec<u64> {
-    line.replace("Time:", "")
-        .trim()
-        .split(' ')
-        .filter(|r| !r.is_empty())
-        .map(|t| t.trim().parse().unwrap())
-        .collect()
+fn parse_times(line: &str) -> Result<Vec<u64>, Unexpected<'_>> {
+    parse_numbers(line, "Time:")
 }
 
-fn parse_distances(line: &str) -> Vec<u64> {
-    line.replace("Distance:", "")
-        .trim()
-        .split(' ')
-        .filter(|r| !r.is_empty())
-        .map(|d| d.trim().parse().unwrap())
-        .collect()
+fn parse_distances(line: &str) -> Result<Vec<u64>, Unexpected<'_>> {
+    parse_numbers(line, "Distance:")
+}
+
+fn parse_numbers<'a>(line: &'a str, header: &'static str) -> Result<Vec<u64>, Unexpected<'a>> {
+    let numbers = strip_header(line, header)?
+        .split_whitespace()
+        .map(error::number)
+        .collect::<Result<Vec<_>, _>>()?;
+    if numbers.is_empty() {
+        Err(Unexpected::end_of(line, "at least one number"))
+    } else {
+        Ok(numbers)
+    }
+}
+
+fn strip_header<'a>(line: &'a str, header: &'static str) -> Result<&'a str, Unexpected<'a>> {
+    line.strip_prefix(header)
+        .ok_or_else(|| Unexpected::new(line, header))
 }
 
 fn count_number_of_ways_to_win(race: &Race) -> u64 {
-    (0..race.time)
-        .map(|ms_holding| {
-            let speed = ms_holding;
-            speed * (race.time - ms_holding)
+    let time = race.time;
+    let beats =
+        |hold: u64| u128::from(hold) * u128::from(time - hold) > u128::from(race.best_distance);
+    if !beats(time / 2) {
+        return 0;
+    }
+    let (mut shortest, mut longest) = (0, time / 2);
+    while shortest < longest {
+        let middle = (shortest + longest) / 2;
+        if beats(middle) {
+            longest = middle;
+        } else {
+            shortest = middle + 1;
+        }
+    }
+    time - 2 * shortest + 1
+}
+
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let races = (0..size.clamp(1, 3))
+        .map(|_| {
+            let time = rng.between(7, 40);
+            let hold = rng.between(1, time / 2 - 1);
+            (time, hold * (time - hold), hold)
         })
-        .filter(|distance| distance > &race.best_distance)
-        .count()
-        .try_into()
-        .unwrap()
+        .collect::<Vec<_>>();
+
+    let (mut times, mut distances) = (format!("{:<9}", "Time:"), "Distance:".to_string());
+    for (time, distance, _) in &races {
+        let width = distance.to_string().len() + 2;
+        write!(times, "{:>width$}", time).unwrap();
+        write!(distances, "{:>width$}", distance).unwrap();
+    }
+    let kerned = |numbers: Vec<u64>| {
+        numbers
+            .iter()
+            .map(u64::to_string)
+            .collect::<String>()
+            .parse::<u64>()
+            .unwrap()
+    };
+    // The record was set holding `hold`, so only the holds strictly between
+    // it and `time - hold` go further.
+    let part1 = races
+        .iter()
+        .map(|&(time, _, hold)| time - 2 * hold - 1)
+        .product::<u64>();
+    let time = kerned(races.iter().map(|race| race.0).collect());
+    let distance = kerned(races.iter().map(|race| race.1).collect());
+    let part2 = (0..=time)
+        .filter(|hold| hold * (time - hold) > distance)
+        .count() as u64;
+    Generated {
+        input: format!("{}\n{}\n", times, distances),
+        expected: vec![(1, Answer::from(part1)), (2, Answer::from(part2))],
+    }
+}
+
+fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+    let races = if part == 1 {
+        parse_input(input)
+    } else {
+        parse_input2(input).map(|race| vec![race])
+    }
+    .map_err(|e| e.locate(DAY, input))?;
+    let ways = races
+        .iter()
+        .map(|race| {
+            (0..=race.time)
+                .filter(|&hold| {
+                    u128::from(hold) * u128::from(race.time - hold) > u128::from(race.best_distance)
+                })
+                .count() as u64
+        })
+        .try_fold(1_u64, |product, ways| product.checked_mul(ways))
+        .ok_or_else(|| ParseError::whole_input(DAY, "a product of ways to win below 2^64"))?;
+    Ok(Answer::from(ways))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::answers;
 
     #[test]
-    fn sample_inputs_part_1() {
-        let input = "Time:      7  15   30
-Distance:  9  40  200
-";
-        assert_eq!(part1(input), 288);
+    fn solutions() {
+        answers::assert_solution::<Day06>(DAY, 1);
+        answers::assert_solution::<Day06>(DAY, 2);
     }
 
     #[test]
-    fn sample_inputs_part_2() {
+    fn missing_distances() {
         let input = "Time:      7  15   30
-Distance:  9  40  200
+Distance:  9  40
 ";
-        assert_eq!(part2(input), 71503);
-    }
-
-    #[test]
-    fn solutions() {
-        let input = include_str!("../input/day06.txt");
-        assert_eq!(part1(input), 505494);
-        assert_eq!(part2(input), 23632299);
+        let error = parse_races(input).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 17));
+        assert_eq!(error.expected, "one distance per time");
     }
 }
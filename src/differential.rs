@@ -0,0 +1,176 @@
+use crate::generate::Rng;
+use crate::normalize;
+use crate::registry::{Puzzle, Reference};
+use crate::runner;
+use crate::Answer;
+use std::fmt;
+use std::ops::Range;
+use std::panic;
+
+pub const DEFAULT_RUNS: u64 = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Disagreement {
+    pub day: u8,
+    pub part: u8,
+    pub seed: u64,
+    pub size: usize,
+    pub solution: Result<Answer, String>,
+    pub reference: Result<Answer, String>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |answer: &Result<Answer, String>| match answer {
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("failed ({})", e.lines().next().unwrap_or("")),
+        };
+        write!(
+            f,
+            "day {:02} part {} disagrees on seed {} size {}: solution {}, reference {}\n\
+             reproduce with `aoc generate {} --seed {} --size {}`",
+            self.day,
+            self.part,
+            self.seed,
+            self.size,
+            show(&self.solution),
+            show(&self.reference),
+            self.day,
+            self.seed,
+            self.size
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Unsupported,
+    Agreed { runs: u64 },
+    Disagreed(Disagreement),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub part: u8,
+    pub solution: Result<Answer, String>,
+    pub reference: Result<Answer, String>,
+}
+
+pub fn compare(puzzle: &Puzzle, seeds: Range<u64>, size: usize, parts: &[u8]) -> Outcome {
+    let (Some(generate), Some(reference)) = (puzzle.generate, puzzle.reference) else {
+        return Outcome::Unsupported;
+    };
+    let runs = seeds.end.saturating_sub(seeds.start);
+    for seed in seeds {
+        let generated = generate(&mut Rng::new(seed), size);
+        if let Some(difference) = difference(puzzle, reference, &generated.input, parts) {
+            return Outcome::Disagreed(Disagreement {
+                day: puzzle.day,
+                part: difference.part,
+                seed,
+                size,
+                solution: difference.solution,
+                reference: difference.reference,
+            });
+        }
+    }
+    Outcome::Agreed { runs }
+}
+
+pub fn difference(
+    puzzle: &Puzzle,
+    reference: Reference,
+    input: &str,
+    parts: &[u8],
+) -> Option<Difference> {
+    let input = normalize::normalize(input);
+    let report = (puzzle.solve)(puzzle.day, &input, parts);
+    for (&part, result) in parts.iter().zip(report.parts) {
+        let reference = match panic::catch_unwind(|| reference(&input, part)) {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(e)) => Err(e.render()),
+            Err(payload) => Err(runner::panic_message(payload)),
+        };
+        let agreed = match (&result.answer, &reference) {
+            (Ok(solution), Ok(reference)) => solution == reference,
+            (Err(solution), Err(reference)) => {
+                !runner::is_panic(solution) && !runner::is_panic(reference)
+            }
+            _ => false,
+        };
+        if !agreed {
+            return Some(Difference {
+                part,
+                solution: result.answer,
+                reference,
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::generate::Generated;
+    use crate::registry::PUZZLES;
+
+    #[test]
+    fn references_agree_on_generated_inputs() {
+        for puzzle in PUZZLES {
+            let outcome = compare(puzzle, 0..5, 20, &[1, 2]);
+            assert!(!matches!(outcome, Outcome::Disagreed(_)), "{:?}", outcome);
+        }
+    }
+
+    #[test]
+    fn reports_the_first_disagreement() {
+        fn generate(rng: &mut Rng, _size: usize) -> Generated {
+            Generated {
+                input: format!("{}\n", rng.below(10)),
+                expected: vec![],
+            }
+        }
+        fn reference(input: &str, _part: u8) -> Result<Answer, ParseError> {
+            let n = input.trim().parse::<u64>().unwrap();
+            Ok(Answer::from(if n == 7 { 0 } else { n * 11 }))
+        }
+        let day01 = PUZZLES.iter().find(|puzzle| puzzle.day == 1).unwrap();
+        let puzzle = Puzzle {
+            generate: Some(generate),
+            reference: Some(reference),
+            ..*day01
+        };
+
+        let Outcome::Disagreed(disagreement) = compare(&puzzle, 0..1000, 1, &[1]) else {
+            panic!("expected a disagreement");
+        };
+        assert_eq!(disagreement.part, 1);
+        assert_eq!(disagreement.reference, Ok(Answer::from(0_u64)));
+        assert_eq!(disagreement.solution, Ok(Answer::from(77_u64)));
+        let first = (0..).find(|&seed| Rng::new(seed).below(10) == 7).unwrap();
+        assert_eq!(disagreement.seed, first);
+
+        let unsupported = Puzzle {
+            reference: None,
+            ..puzzle
+        };
+        assert_eq!(compare(&unsupported, 0..10, 1, &[1]), Outcome::Unsupported);
+    }
+    #[test]
+    fn a_panic_never_agrees() {
+        fn reference(_input: &str, _part: u8) -> Result<Answer, ParseError> {
+            panic!("reference crashed")
+        }
+        let day01 = PUZZLES.iter().find(|puzzle| puzzle.day == 1).unwrap();
+        let crashed = difference(day01, reference, "no digits here\n", &[1]).unwrap();
+        assert!(crashed.solution.is_err());
+        assert!(runner::is_panic(crashed.reference.as_ref().unwrap_err()));
+
+        fn failing(_input: &str, _part: u8) -> Result<Answer, ParseError> {
+            Err(ParseError::whole_input(1, "a digit"))
+        }
+        assert_eq!(difference(day01, failing, "no digits here\n", &[1]), None);
+    }
+}
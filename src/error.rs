@@ -0,0 +1,142 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub line_content: String,
+}
+
+impl ParseError {
+    pub fn whole_input(day: u8, expected: &str) -> ParseError {
+        ParseError {
+            day,
+            line: 1,
+            column: 1,
+            expected: expected.to_string(),
+            line_content: String::new(),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let gutter = self.line.to_string().len();
+        // Widths are spelled out because format widths are capped at 2^16.
+        format!(
+            "{}\n{:>gutter$} |\n{} | {}\n{:>gutter$} | {}^",
+            self,
+            "",
+            self.line,
+            self.line_content,
+            "",
+            " ".repeat(self.column.saturating_sub(1)),
+            gutter = gutter,
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {:02}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unexpected<'a> {
+    pub at: &'a str,
+    pub expected: &'static str,
+}
+
+impl<'a> Unexpected<'a> {
+    pub fn new(at: &'a str, expected: &'static str) -> Self {
+        Unexpected { at, expected }
+    }
+
+    pub fn end_of(fragment: &'a str, expected: &'static str) -> Self {
+        Unexpected::new(&fragment[fragment.len()..], expected)
+    }
+
+    pub fn locate_line(self, day: u8, line: usize, content: &str) -> ParseError {
+        ParseError {
+            line,
+            ..self.locate(day, content)
+        }
+    }
+
+    pub fn locate(self, day: u8, input: &str) -> ParseError {
+        let offset = (self.at.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .unwrap_or(0);
+        let line_start = input[..offset].rfind('\n').map_or(0, |pos| pos + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |pos| offset + pos);
+        ParseError {
+            day,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            expected: self.expected.to_string(),
+            line_content: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+}
+
+pub fn number<T: FromStr>(raw: &str) -> Result<T, Unexpected<'_>> {
+    raw.parse().map_err(|_| Unexpected::new(raw, "a number"))
+}
+
+pub fn split_once<'a>(
+    raw: &'a str,
+    separator: &'static str,
+    expected: &'static str,
+) -> Result<(&'a str, &'a str), Unexpected<'a>> {
+    raw.split_once(separator)
+        .ok_or_else(|| Unexpected::end_of(raw, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate_fragment() {
+        let input = "Game 1: 3 blue\nGame x: 4 red\n";
+        let at = &input[20..21];
+        let error = Unexpected::new(at, "a number").locate(2, input);
+        assert_eq!(
+            error,
+            ParseError {
+                day: 2,
+                line: 2,
+                column: 6,
+                expected: "a number".to_string(),
+                line_content: "Game x: 4 red".to_string(),
+            }
+        );
+        assert_eq!(
+            error.render(),
+            "day 02, line 2, column 6: expected a number
+  |
+2 | Game x: 4 red
+  |      ^"
+        );
+    }
+
+    #[test]
+    fn locate_end_of_input() {
+        let input = "Time: 7";
+        let error = Unexpected::end_of(input, "a newline").locate(6, input);
+        assert_eq!((error.line, error.column), (1, 8));
+    }
+}
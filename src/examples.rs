@@ -0,0 +1,115 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub day: u8,
+    pub name: String,
+    pub input: String,
+    pub expected: Vec<(u8, String)>,
+}
+
+pub fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("examples")
+}
+
+pub fn day_dir(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{:02}", day))
+}
+
+pub fn discover(dir: &Path, day: u8) -> io::Result<Vec<Example>> {
+    let day_dir = day_dir(dir, day);
+    let entries = match fs::read_dir(&day_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+
+    let mut inputs = entries
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect::<Vec<_>>();
+    inputs.sort();
+
+    inputs
+        .into_iter()
+        .map(|path| {
+            let expected_path = path.with_extension("expected");
+            let expected = fs::read_to_string(&expected_path).map_err(|e| {
+                io::Error::new(e.kind(), format!("{}: {}", expected_path.display(), e))
+            })?;
+            Ok(Example {
+                day,
+                name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+                input: fs::read_to_string(&path)?,
+                expected: parse_expected(&expected).map_err(|line| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "{}:{}: expected `part1: ANSWER` or `part2: ANSWER`",
+                            expected_path.display(),
+                            line
+                        ),
+                    )
+                })?,
+            })
+        })
+        .collect()
+}
+
+fn parse_expected(content: &str) -> Result<Vec<(u8, String)>, usize> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            let (part, answer) = line.split_once(':').ok_or(number + 1)?;
+            let part = match part.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(number + 1),
+            };
+            Ok((part, answer.trim().to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::PUZZLES;
+
+    #[test]
+    fn parse_expected_answers() {
+        assert_eq!(
+            parse_expected("part1: 142\n\npart2: 281\n"),
+            Ok(vec![(1, "142".to_string()), (2, "281".to_string())])
+        );
+        assert_eq!(parse_expected("part3: 1\n"), Err(1));
+        assert_eq!(parse_expected("part1: 1\npart2 2\n"), Err(2));
+    }
+
+    #[test]
+    fn registered_days_solve_their_examples() {
+        let mut failures = vec![];
+        for puzzle in PUZZLES {
+            for example in discover(&examples_dir(), puzzle.day).unwrap() {
+                let parts = example.expected.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+                let report = (puzzle.solve)(puzzle.day, &example.input, &parts);
+                for ((part, expected), result) in example.expected.iter().zip(&report.parts) {
+                    let answer = result.answer.as_ref().map(|a| a.to_string());
+                    if answer.as_ref() != Ok(expected) {
+                        failures.push(format!(
+                            "day {:02} {} part {}: expected {}, got {:?}",
+                            puzzle.day, example.name, part, expected, answer
+                        ));
+                    }
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
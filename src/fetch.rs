@@ -0,0 +1,189 @@
+use crate::http::{self, HttpError};
+use crate::input;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const YEAR: u16 = 2023;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+pub const USER_AGENT: &str = "advent-of-code-2023 runner";
+
+#[derive(Debug)]
+pub enum FetchError {
+    MissingSession(Option<PathBuf>),
+    Http(HttpError),
+    Status { day: u8, status: u16, body: String },
+    Write { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::MissingSession(Some(config)) => write!(
+                f,
+                "no session token: set {} or write it to {}",
+                SESSION_VAR,
+                config.display()
+            ),
+            FetchError::MissingSession(None) => write!(f, "no session token: set {}", SESSION_VAR),
+            FetchError::Http(e) => write!(f, "{}", e),
+            FetchError::Status { day, status, body } => write!(
+                f,
+                "downloading day {} failed with status {}: {}",
+                day,
+                status,
+                body.lines().next().unwrap_or("").trim()
+            ),
+            FetchError::Write { path, source } => {
+                write!(f, "can't write {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+pub fn base_url() -> String {
+    std::env::var(BASE_URL_VAR)
+        .ok()
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+}
+
+pub fn session_config_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("aoc").join("session"))
+}
+
+pub fn session() -> Result<String, FetchError> {
+    let config_path = session_config_path();
+    resolve_session(std::env::var(SESSION_VAR).ok(), config_path.as_deref())
+        .ok_or(FetchError::MissingSession(config_path))
+}
+
+fn resolve_session(env_session: Option<String>, config_path: Option<&Path>) -> Option<String> {
+    env_session
+        .or_else(|| config_path.and_then(|path| fs::read_to_string(path).ok()))
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+pub fn fetch(
+    day: u8,
+    input_dir: &Path,
+    base_url: &str,
+    session: &str,
+) -> Result<Fetched, FetchError> {
+    let path = input::input_path(input_dir, day);
+    if fs::metadata(&path).is_ok_and(|meta| meta.len() > 0) {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let url = format!(
+        "{}/{}/day/{}/input",
+        base_url.trim_end_matches('/'),
+        YEAR,
+        day
+    );
+    let cookie = format!("session={}", session);
+    let response = http::get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])
+        .map_err(FetchError::Http)?;
+    if response.status != 200 {
+        return Err(FetchError::Status {
+            day,
+            status: response.status,
+            body: response.body,
+        });
+    }
+
+    write_atomically(&path, &response.body).map_err(|source| FetchError::Write {
+        path: path.clone(),
+        source,
+    })?;
+    Ok(Fetched::Downloaded(path))
+}
+
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("txt.partial");
+    fs::write(&partial, content)?;
+    fs::rename(&partial, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server;
+    use std::env;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn downloads_once_then_uses_cache() {
+        let dir = temp_dir("cache");
+        let server = test_server::serve(vec![test_server::response(200, "Time: 7\nDistance: 9\n")]);
+
+        let fetched = fetch(6, &dir, &server.base_url, "s3cr3t").unwrap();
+        assert_eq!(fetched, Fetched::Downloaded(dir.join("day06.txt")));
+        assert_eq!(
+            fs::read_to_string(dir.join("day06.txt")).unwrap(),
+            "Time: 7\nDistance: 9\n"
+        );
+        let request = server.requests.recv().unwrap();
+        assert!(request.starts_with("GET /2023/day/6/input HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=s3cr3t\r\n"));
+
+        let fetched = fetch(6, &dir, &server.base_url, "s3cr3t").unwrap();
+        assert_eq!(fetched, Fetched::Cached(dir.join("day06.txt")));
+        assert!(server.requests.try_recv().is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_download_leaves_no_file() {
+        let dir = temp_dir("failure");
+        let server = test_server::serve(vec![test_server::response(
+            400,
+            "Please don't repeatedly request this endpoint before it unlocks!",
+        )]);
+
+        let error = fetch(7, &dir, &server.base_url, "s3cr3t").unwrap_err();
+        assert!(matches!(error, FetchError::Status { status: 400, .. }));
+        assert!(!dir.join("day07.txt").exists());
+    }
+
+    #[test]
+    fn session_resolution() {
+        let config = temp_dir("session");
+        fs::write(&config, "  from-config\n").unwrap();
+        assert_eq!(
+            resolve_session(Some("from-env".to_string()), Some(&config)),
+            Some("from-env".to_string())
+        );
+        assert_eq!(
+            resolve_session(None, Some(&config)),
+            Some("from-config".to_string())
+        );
+        assert_eq!(resolve_session(None, Some(&temp_dir("missing"))), None);
+        assert_eq!(resolve_session(Some(" ".to_string()), None), None);
+        fs::remove_file(config).unwrap();
+    }
+}
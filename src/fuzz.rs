@@ -0,0 +1,186 @@
+use crate::examples;
+use crate::generate::Rng;
+use crate::registry::{Puzzle, PUZZLES};
+use crate::runner;
+
+const FUZZ_SEED: u64 = 0x2023_1201;
+
+const UNICODE: [&str; 8] = ["é", "€", "ß", "٣", "Ⅻ", "𝟘", "\u{200b}", "\u{feff}"];
+
+const LONG_LINE: usize = 1_000_000;
+const LONG_LINE_EVERY: usize = 50;
+const SHOWN_INPUT: usize = 500;
+
+const HUGE_NUMBERS: [&str; 5] = [
+    "4294967296",
+    "18446744073709551615",
+    "18446744073709551616",
+    "99999999999999999999999999999999",
+    "000000000000000000000000000000001",
+];
+
+fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut mutated = input.to_string();
+    for _ in 0..rng.between(1, 3) {
+        mutated = match rng.below(7) {
+            0 => flip_byte(rng, &mutated),
+            1 => {
+                let inserted = *rng.pick(&UNICODE);
+                insert_at_boundary(rng, &mutated, inserted)
+            }
+            2 => mutated[..char_boundary(rng, &mutated)].to_string(),
+            3 => mutated.replace('\n', "\r\n"),
+            4 => replace_number(rng, &mutated),
+            5 => remove_span(rng, &mutated),
+            _ => duplicate_line(rng, &mutated),
+        };
+    }
+    mutated
+}
+
+fn char_boundary(rng: &mut Rng, input: &str) -> usize {
+    let boundaries = input
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([input.len()])
+        .collect::<Vec<_>>();
+    *rng.pick(&boundaries)
+}
+
+fn flip_byte(rng: &mut Rng, input: &str) -> String {
+    if input.is_empty() {
+        return input.to_string();
+    }
+    let mut bytes = input.as_bytes().to_vec();
+    let index = rng.below(bytes.len() as u64) as usize;
+    bytes[index] ^= 1 << rng.below(8);
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn insert_at_boundary(rng: &mut Rng, input: &str, inserted: &str) -> String {
+    let at = char_boundary(rng, input);
+    format!("{}{}{}", &input[..at], inserted, &input[at..])
+}
+
+fn replace_number(rng: &mut Rng, input: &str) -> String {
+    let starts = input
+        .char_indices()
+        .filter(|&(i, c)| c.is_ascii_digit() && !input[..i].ends_with(|p: char| p.is_ascii_digit()))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if starts.is_empty() {
+        let huge = *rng.pick(&HUGE_NUMBERS);
+        return insert_at_boundary(rng, input, huge);
+    }
+    let start = *rng.pick(&starts);
+    let end = input[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(input.len(), |len| start + len);
+    format!(
+        "{}{}{}",
+        &input[..start],
+        rng.pick(&HUGE_NUMBERS),
+        &input[end..]
+    )
+}
+
+fn remove_span(rng: &mut Rng, input: &str) -> String {
+    let (a, b) = (char_boundary(rng, input), char_boundary(rng, input));
+    format!("{}{}", &input[..a.min(b)], &input[a.max(b)..])
+}
+
+fn pad_line(rng: &mut Rng, input: &str) -> String {
+    let mut lines = input.split('\n').map(str::to_string).collect::<Vec<_>>();
+    let index = rng.below(lines.len() as u64) as usize;
+    let padding = LONG_LINE.saturating_sub(lines[index].len());
+    lines[index].push_str(&".".repeat(padding));
+    lines.join("\n")
+}
+
+fn duplicate_line(rng: &mut Rng, input: &str) -> String {
+    let mut lines = input.split_inclusive('\n').collect::<Vec<_>>();
+    if !lines.is_empty() {
+        let line = *rng.pick(&lines);
+        lines.insert(rng.below(lines.len() as u64 + 1) as usize, line);
+    }
+    lines.concat()
+}
+
+fn corpus(puzzle: &Puzzle) -> Vec<String> {
+    let mut corpus = examples::discover(&examples::examples_dir(), puzzle.day)
+        .unwrap()
+        .into_iter()
+        .map(|example| example.input)
+        .collect::<Vec<_>>();
+    if let Some(generate) = puzzle.generate {
+        corpus.extend((0..3).map(|seed| generate(&mut Rng::new(seed), 10).input));
+    }
+    corpus
+}
+
+fn fuzz(seed: u64, iterations: usize) {
+    let mut rng = Rng::new(seed);
+    let failures = PUZZLES
+        .iter()
+        .filter_map(|puzzle| fuzz_puzzle(&mut rng, puzzle, &corpus(puzzle), iterations))
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
+
+fn fuzz_puzzle(
+    rng: &mut Rng,
+    puzzle: &Puzzle,
+    corpus: &[String],
+    iterations: usize,
+) -> Option<String> {
+    if corpus.is_empty() {
+        return None;
+    }
+    for iteration in 0..iterations {
+        let original = rng.pick(corpus).clone();
+        let mut input = mutate(rng, &original);
+        if iteration % LONG_LINE_EVERY == 0 {
+            input = pad_line(rng, &input);
+        }
+        let report = (puzzle.solve)(puzzle.day, &input, &[1, 2]);
+        if let Some(panic) = report
+            .parts
+            .iter()
+            .filter_map(|part| part.answer.as_ref().err())
+            .find(|message| runner::is_panic(message))
+        {
+            let shown = &input[..input.floor_char_boundary(SHOWN_INPUT)];
+            return Some(format!(
+                "day {:02} iteration {}: {}\ninput ({} bytes): {:?}",
+                puzzle.day,
+                iteration,
+                panic,
+                input.len(),
+                shown
+            ));
+        }
+    }
+    None
+}
+
+mod scaffolded {
+    include!(concat!(env!("OUT_DIR"), "/scaffolded.rs"));
+}
+
+#[test]
+fn parsers_survive_mutations() {
+    fuzz(FUZZ_SEED, 300);
+}
+
+#[test]
+#[ignore = "long-running, run with `cargo test -- --ignored`"]
+fn parsers_survive_many_mutations() {
+    fuzz(FUZZ_SEED, 50_000);
+}
+
+#[test]
+fn scaffolded_day_survives_mutations() {
+    let corpus = ["first line\nsecond line\n".to_string()];
+    let failure = fuzz_puzzle(&mut Rng::new(FUZZ_SEED), &scaffolded::PUZZLE, &corpus, 300);
+    assert_eq!(failure, None);
+}
@@ -0,0 +1,107 @@
+use crate::Answer;
+use std::fmt::Write as _;
+
+pub const DEFAULT_SEED: u64 = 2023;
+pub const DEFAULT_SIZE: usize = 100;
+
+pub type Generator = fn(&mut Rng, usize) -> Generated;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub expected: Vec<(u8, Answer)>,
+}
+
+impl Generated {
+    pub fn render_expected(&self) -> String {
+        let mut content = String::new();
+        for (part, answer) in &self.expected {
+            writeln!(content, "part{}: {}", part, answer).unwrap();
+        }
+        content
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+
+    pub fn between(&mut self, low: u64, high: u64) -> u64 {
+        low + self.below(high - low + 1)
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::PUZZLES;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        let draws = (0..100).map(|_| a.between(3, 9)).collect::<Vec<_>>();
+        assert_eq!(draws, (0..100).map(|_| b.between(3, 9)).collect::<Vec<_>>());
+        assert!(draws.iter().all(|n| (3..=9).contains(n)));
+        assert!((3..=9).all(|n| draws.contains(&n)));
+        assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+    }
+
+    #[test]
+    fn registered_days_solve_generated_inputs() {
+        let mut failures = vec![];
+        for puzzle in PUZZLES {
+            let Some(generate) = puzzle.generate else {
+                continue;
+            };
+            for seed in 0..5 {
+                let generated = generate(&mut Rng::new(seed), 20);
+                let parts = generated
+                    .expected
+                    .iter()
+                    .map(|(p, _)| *p)
+                    .collect::<Vec<_>>();
+                let report = (puzzle.solve)(puzzle.day, &generated.input, &parts);
+                for ((part, expected), result) in generated.expected.iter().zip(&report.parts) {
+                    if result.answer.as_ref() != Ok(expected) {
+                        failures.push(format!(
+                            "day {:02} seed {} part {}: expected {}, got {:?}",
+                            puzzle.day, seed, part, expected, result.answer
+                        ));
+                    }
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,336 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    InvalidUrl(String),
+    Io(io::Error),
+    Curl(String),
+    MalformedResponse,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::InvalidUrl(url) => write!(f, "invalid url {}", url),
+            HttpError::Io(e) => write!(f, "{}", e),
+            HttpError::Curl(e) => write!(f, "curl failed: {}", e),
+            HttpError::MalformedResponse => write!(f, "malformed HTTP response"),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<io::Error> for HttpError {
+    fn from(e: io::Error) -> Self {
+        HttpError::Io(e)
+    }
+}
+
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response, HttpError> {
+    request("GET", url, headers, None)
+}
+
+pub fn post_form(
+    url: &str,
+    headers: &[(&str, &str)],
+    fields: &[(&str, &str)],
+) -> Result<Response, HttpError> {
+    let body = fields
+        .iter()
+        .map(|(name, value)| format!("{}={}", form_encode(name), form_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    let mut headers = headers.to_vec();
+    headers.push(("Content-Type", "application/x-www-form-urlencoded"));
+    request("POST", url, &headers, Some(&body))
+}
+
+fn form_encode(raw: &str) -> String {
+    let mut encoded = String::new();
+    for byte in raw.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, HttpError> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        plain_request(method, rest, headers, body)
+    } else if url.starts_with("https://") {
+        curl_request(method, url, headers, body)
+    } else {
+        Err(HttpError::InvalidUrl(url.to_string()))
+    }
+}
+
+fn plain_request(
+    method: &str,
+    host_and_path: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, HttpError> {
+    let (authority, path) = match host_and_path.find('/') {
+        Some(pos) => host_and_path.split_at(pos),
+        None => (host_and_path, "/"),
+    };
+    if authority.is_empty() {
+        return Err(HttpError::InvalidUrl(format!("http://{}", host_and_path)));
+    }
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        method, path, authority
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if let Some(body) = body {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    request.push_str(body.unwrap_or(""));
+    stream.write_all(request.as_bytes())?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+    parse_response(&String::from_utf8_lossy(&raw))
+}
+
+fn curl_request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, HttpError> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--include", "--request", method])
+        .args(["--header", "@-"]);
+    if let Some(body) = body {
+        command.args(["--data-binary", body]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| HttpError::Curl(e.to_string()))?;
+
+    let mut stdin = child.stdin.take().unwrap();
+    for (name, value) in headers {
+        writeln!(stdin, "{}: {}", name, value)?;
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(HttpError::Curl(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    parse_response(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_response(mut raw: &str) -> Result<Response, HttpError> {
+    while raw.starts_with("HTTP/1.1 1") || raw.starts_with("HTTP/2 1") {
+        raw = raw
+            .split_once("\r\n\r\n")
+            .ok_or(HttpError::MalformedResponse)?
+            .1;
+    }
+    let (head, body) = raw
+        .split_once("\r\n\r\n")
+        .ok_or(HttpError::MalformedResponse)?;
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or(HttpError::MalformedResponse)?;
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    let chunked = headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("transfer-encoding") && value.eq_ignore_ascii_case("chunked")
+    });
+    let body = if chunked {
+        decode_chunked(body).ok_or(HttpError::MalformedResponse)?
+    } else {
+        body.to_string()
+    };
+    Ok(Response {
+        status,
+        headers,
+        body,
+    })
+}
+
+fn decode_chunked(mut raw: &str) -> Option<String> {
+    let mut body = String::new();
+    loop {
+        let (size, rest) = raw.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(body);
+        }
+        body.push_str(rest.get(..size)?);
+        raw = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_server {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    pub struct TestServer {
+        pub base_url: String,
+        pub requests: Receiver<String>,
+    }
+
+    pub fn serve(responses: Vec<String>) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8(body).unwrap());
+                sender.send(request).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        TestServer { base_url, requests }
+    }
+
+    pub fn response(status: u16, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_from_local_server() {
+        let server = test_server::serve(vec![test_server::response(200, "hello")]);
+        let response = get(
+            &format!("{}/path?q=1", server.base_url),
+            &[("X-Test", "yes")],
+        )
+        .unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "hello");
+        assert_eq!(response.header("content-length"), Some("5"));
+
+        let request = server.requests.recv().unwrap();
+        assert!(request.starts_with("GET /path?q=1 HTTP/1.1\r\n"));
+        assert!(request.contains("X-Test: yes\r\n"));
+    }
+
+    #[test]
+    fn post_form_to_local_server() {
+        let server = test_server::serve(vec![test_server::response(200, "ok")]);
+        let response = post_form(
+            &format!("{}/submit", server.base_url),
+            &[],
+            &[("level", "1"), ("answer", "a b&c")],
+        )
+        .unwrap();
+        assert_eq!(response.body, "ok");
+
+        let request = server.requests.recv().unwrap();
+        assert!(request.starts_with("POST /submit HTTP/1.1\r\n"));
+        assert!(request.contains("Content-Type: application/x-www-form-urlencoded\r\n"));
+        assert!(request.contains("Content-Length: 22\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=1&answer=a+b%26c"));
+    }
+
+    #[test]
+    fn chunked_response() {
+        let response =
+            parse_response("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n")
+                .unwrap();
+        assert_eq!(response.body, "Wikipedia");
+    }
+
+    #[test]
+    fn unsupported_scheme() {
+        assert!(matches!(
+            get("ftp://example.com", &[]),
+            Err(HttpError::InvalidUrl(_))
+        ));
+    }
+}
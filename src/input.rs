@@ -0,0 +1,118 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_INPUT_DIR: &str = "input";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    Default,
+    Path(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing { day: u8, path: PathBuf },
+    Unreadable { path: PathBuf, source: io::Error },
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { day, path } => write!(
+                f,
+                "no input for day {}: {} does not exist (set {} or pass --input)",
+                day,
+                path.display(),
+                INPUT_DIR_VAR
+            ),
+            InputError::Unreadable { path, source } => {
+                write!(f, "can't read {}: {}", path.display(), source)
+            }
+            InputError::Stdin(source) => write!(f, "can't read stdin: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+pub fn input_dir() -> PathBuf {
+    resolve_input_dir(env::var_os(INPUT_DIR_VAR))
+}
+
+fn resolve_input_dir(env_dir: Option<OsString>) -> PathBuf {
+    env_dir
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR))
+}
+
+pub fn input_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{:02}.txt", day))
+}
+
+pub fn load(day: u8, source: &InputSource) -> Result<String, InputError> {
+    match source {
+        InputSource::Default => read_file(day, &input_path(&input_dir(), day)),
+        InputSource::Path(path) => read_file(day, path),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(InputError::Stdin)?;
+            Ok(input)
+        }
+    }
+}
+
+pub fn load_day(day: u8) -> Result<String, InputError> {
+    load(day, &InputSource::Default)
+}
+
+fn read_file(day: u8, path: &Path) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => InputError::Missing {
+            day,
+            path: path.to_path_buf(),
+        },
+        _ => InputError::Unreadable {
+            path: path.to_path_buf(),
+            source,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_dir_resolution() {
+        assert_eq!(resolve_input_dir(None), PathBuf::from("input"));
+        assert_eq!(resolve_input_dir(Some("".into())), PathBuf::from("input"));
+        assert_eq!(
+            resolve_input_dir(Some("/tmp/inputs".into())),
+            PathBuf::from("/tmp/inputs")
+        );
+        assert_eq!(
+            input_path(Path::new("/tmp/inputs"), 5),
+            PathBuf::from("/tmp/inputs/day05.txt")
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        let source = InputSource::Path(PathBuf::from("input/does-not-exist.txt"));
+        let error = load(3, &source).unwrap_err();
+        assert!(matches!(error, InputError::Missing { day: 3, .. }));
+        assert_eq!(
+            error.to_string(),
+            "no input for day 3: input/does-not-exist.txt does not exist (set AOC_INPUT_DIR or pass --input)"
+        );
+    }
+}
@@ -1,6 +1,72 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
+use error::ParseError;
+use std::fmt;
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod cli;
+pub mod differential;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+#[cfg(test)]
+mod fuzz;
+pub mod generate;
+pub mod http;
+pub mod input;
+pub mod minimize;
+pub mod normalize;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod submit;
+
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::Number(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Number(n.try_into().unwrap())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(t: String) -> Self {
+        Answer::Text(t)
+    }
+}
+
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Result<Answer, ParseError>;
+    fn part2(input: &Self::Input) -> Result<Answer, ParseError>;
+}
@@ -1,16 +1,577 @@
-use advent_of_code::*;
-
-fn main() {
-    println!("-------- DAY 01 --------");
-    day01::main();
-    println!("-------- DAY 02 --------");
-    day02::main();
-    println!("-------- DAY 03 --------");
-    day03::main();
-    println!("-------- DAY 04 --------");
-    day04::main();
-    println!("-------- DAY 05 --------");
-    day05::main();
-    println!("-------- DAY 06 --------");
-    day06::main();
+use advent_of_code::answers::{self, Answers};
+use advent_of_code::bench::{self, BenchOptions};
+use advent_of_code::cli::{self, Command, RunArgs};
+use advent_of_code::day01::{self, Document, Extraction, Lexicon, MissingDigits};
+use advent_of_code::differential::{self, Outcome};
+use advent_of_code::fetch::{self, Fetched};
+use advent_of_code::generate::Rng;
+use advent_of_code::input::{self, InputSource};
+use advent_of_code::minimize::{self, Predicate};
+use advent_of_code::normalize;
+use advent_of_code::registry::{self, Puzzle};
+use advent_of_code::report::{self, Format, Status};
+use advent_of_code::runner::{self, DayReport};
+use advent_of_code::scaffold;
+use advent_of_code::submit::{self, Attempts, Verdict};
+use advent_of_code::Answer;
+use rayon::prelude::*;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::panic;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+fn main() -> ExitCode {
+    let args = match cli::parse_args(env::args().skip(1), &registry::days()) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::NewDay { day, title, root }) => return new_day(&root, day, &title),
+        Ok(Command::Fetch { days, base_url }) => {
+            return fetch_inputs(&days, &base_url.unwrap_or_else(fetch::base_url))
+        }
+        Ok(Command::Generate { day, seed, size }) => return generate(day, seed, size),
+        Ok(Command::Diff {
+            days,
+            seed,
+            runs,
+            size,
+        }) => return diff(&days, seed, runs, size),
+        Ok(Command::Minimize {
+            day,
+            predicate,
+            input,
+            output,
+        }) => {
+            let output = output.unwrap_or_else(|| minimize::output_path(day));
+            return minimize_input(day, predicate, &input, &output);
+        }
+        Ok(Command::Audit {
+            input,
+            format,
+            lexicon,
+            ignore_case,
+        }) => return audit(&input, format, lexicon.as_deref(), ignore_case),
+        Ok(Command::Submit {
+            day,
+            part,
+            answer,
+            base_url,
+        }) => {
+            let base_url = base_url.unwrap_or_else(fetch::base_url);
+            return submit_answer(day, part, answer, &base_url);
+        }
+        Ok(Command::List) => {
+            for puzzle in registry::PUZZLES {
+                println!("{:02}  {}", puzzle.day, puzzle.title);
+            }
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let answers_path = answers::answers_path();
+    let mut answers = match Answers::load(&answers_path) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let lexicon = match day01_lexicon(args.lexicon.as_deref(), args.ignore_case) {
+        Ok(lexicon) => lexicon,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    if lexicon.is_some() {
+        // The recorded answers are for the English digits, not for this variant.
+        answers = Answers::default();
+    }
+
+    panic::set_hook(Box::new(|_| {}));
+    let puzzles = registry::PUZZLES
+        .iter()
+        .filter(|puzzle| args.days.contains(&puzzle.day))
+        .collect::<Vec<_>>();
+    if let Some(options) = &args.bench {
+        return exit_code(run_benches(&puzzles, &args, options));
+    }
+
+    let day01_variant = lexicon.is_some() || args.missing_digits != MissingDigits::Fail;
+    let solve = |puzzle: &Puzzle| {
+        if puzzle.day == 1 && day01_variant {
+            load_and_solve_day01(lexicon.as_ref(), &args)
+        } else {
+            load_and_solve(puzzle, &args.parts, &args.input, args.strict)
+        }
+    };
+    let reports = if args.parallel {
+        run_parallel(&puzzles, solve, &args, &answers)
+    } else {
+        let mut reports = vec![];
+        for puzzle in &puzzles {
+            let report = solve(puzzle);
+            if args.format == Format::Text {
+                print_report(&report, &answers);
+            }
+            reports.push(report);
+        }
+        reports
+    };
+    let records = reports
+        .iter()
+        .flat_map(|r| report::records(r, &answers))
+        .collect::<Vec<_>>();
+    match args.format {
+        Format::Text => {}
+        Format::Json => print!("{}", report::render_json(&records)),
+        Format::Csv => print!("{}", report::render_csv(&records)),
+    }
+
+    if args.record {
+        if let Err(e) = record_answers(&reports, &mut answers, &answers_path, args.overwrite) {
+            eprintln!("error: can't write {}: {}", answers_path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    exit_code(records.iter().all(|r| r.status == Status::Ok))
+}
+
+fn new_day(root: &Path, day: u8, title: &str) -> ExitCode {
+    match scaffold::new_day(root, day, title) {
+        Ok(created) => {
+            for path in created {
+                println!("created {}", path.display());
+            }
+            println!("day {:02} is registered on the next build", day);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fetch_inputs(days: &[u8], base_url: &str) -> ExitCode {
+    let session = match fetch::session() {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let input_dir = input::input_dir();
+    let mut succeeded = true;
+    for &day in days {
+        match fetch::fetch(day, &input_dir, base_url, &session) {
+            Ok(Fetched::Cached(path)) => println!("day {:02}: cached in {}", day, path.display()),
+            Ok(Fetched::Downloaded(path)) => {
+                println!("day {:02}: downloaded to {}", day, path.display())
+            }
+            Err(e) => {
+                eprintln!("day {:02}: {}", day, e);
+                succeeded = false;
+            }
+        }
+    }
+    exit_code(succeeded)
+}
+
+fn generate(day: u8, seed: u64, size: usize) -> ExitCode {
+    let puzzle = registry::find(day).expect("solved days are checked by the cli");
+    let Some(generate) = puzzle.generate else {
+        eprintln!("error: day {:02} has no input generator", day);
+        return ExitCode::FAILURE;
+    };
+    let generated = generate(&mut Rng::new(seed), size);
+    print!("{}", generated.input);
+    eprint!("{}", generated.render_expected());
+    ExitCode::SUCCESS
+}
+
+fn diff(days: &[u8], seed: u64, runs: u64, size: usize) -> ExitCode {
+    panic::set_hook(Box::new(|_| {}));
+    let mut succeeded = true;
+    for &day in days {
+        let puzzle = registry::find(day).expect("solved days are checked by the cli");
+        let seeds = seed..seed
+            .checked_add(runs)
+            .expect("seed overflows are checked by the cli");
+        match differential::compare(puzzle, seeds, size, &[1, 2]) {
+            Outcome::Unsupported => {
+                println!("day {:02}: no generator or reference solver, skipped", day)
+            }
+            Outcome::Agreed { runs } => {
+                println!(
+                    "day {:02}: solution and reference agree on {} inputs",
+                    day, runs
+                )
+            }
+            Outcome::Disagreed(disagreement) => {
+                eprintln!("{}", disagreement);
+                succeeded = false;
+            }
+        }
+    }
+    exit_code(succeeded)
+}
+
+fn minimize_input(
+    day: u8,
+    predicate: Predicate,
+    source: &InputSource,
+    output: &std::path::Path,
+) -> ExitCode {
+    let input = match input::load(day, source) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    panic::set_hook(Box::new(|_| {}));
+    let puzzle = registry::find(day).expect("solved days are checked by the cli");
+    let minimized = match minimize::minimize(puzzle, predicate, &input) {
+        Ok(minimized) => minimized,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = std::fs::write(output, &minimized) {
+        eprintln!("error: can't write {}: {}", output.display(), e);
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "reduced {} lines to {}, written to {}",
+        input.lines().count(),
+        minimized.lines().count(),
+        output.display()
+    );
+    ExitCode::SUCCESS
+}
+
+fn audit(
+    source: &InputSource,
+    format: Format,
+    lexicon: Option<&Path>,
+    ignore_case: bool,
+) -> ExitCode {
+    let lexicon = match day01_lexicon(lexicon, ignore_case) {
+        Ok(lexicon) => lexicon.unwrap_or_else(Lexicon::english),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let input = match input::load(1, source) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let document = match normalize::normalize(&input).parse::<Document>() {
+        Ok(document) => document,
+        Err(e) => {
+            eprintln!("error: {}", e.render());
+            return ExitCode::FAILURE;
+        }
+    };
+    let audited = day01::audit(&document, &Lexicon::digits(), &lexicon);
+    match format {
+        Format::Csv => print!("{}", day01::render_audit_csv(&audited)),
+        _ => print!("{}", day01::render_audit_table(&audited)),
+    }
+    ExitCode::SUCCESS
+}
+
+fn submit_answer(day: u8, part: u8, answer: Option<String>, base_url: &str) -> ExitCode {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            panic::set_hook(Box::new(|_| {}));
+            let puzzle = registry::find(day).expect("solved days are checked by the cli");
+            let mut report = load_and_solve(puzzle, &[part], &InputSource::Default, false);
+            match (report.error, report.parts.pop().map(|p| p.answer)) {
+                (None, Some(Ok(answer))) => answer.to_string(),
+                (Some(e), _) | (None, Some(Err(e))) => {
+                    eprintln!("error: day {:02} part {} failed: {}", day, part, e);
+                    return ExitCode::FAILURE;
+                }
+                (None, None) => unreachable!("one part was requested"),
+            }
+        }
+    };
+    let session = match fetch::session() {
+        Ok(session) => session,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let attempts_path = submit::attempts_path();
+    let mut attempts = match Attempts::load(&attempts_path) {
+        Ok(attempts) => attempts,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let attempt = match submit::submit(
+        &mut attempts,
+        base_url,
+        &session,
+        day,
+        part,
+        &answer,
+        submit::now(),
+    ) {
+        Ok(attempt) => attempt,
+        Err(e) if e.refused() => {
+            eprintln!("error: not submitting {}: {}", answer, e);
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!(
+                "error: submitting {} failed, it isn't recorded in {}: {}",
+                answer,
+                attempts_path.display(),
+                e
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = attempts.save(&attempts_path) {
+        eprintln!("error: can't write {}: {}", attempts_path.display(), e);
+    }
+    println!(
+        "day {:02} part {}: {} is {}",
+        day, part, attempt.answer, attempt.verdict
+    );
+    if attempt.wait > 0 {
+        println!("wait {}s before submitting again", attempt.wait);
+    }
+    exit_code(attempt.verdict == Verdict::Correct)
+}
+
+fn exit_code(succeeded: bool) -> ExitCode {
+    if succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn load_and_solve(puzzle: &Puzzle, parts: &[u8], source: &InputSource, strict: bool) -> DayReport {
+    match input::load(puzzle.day, source) {
+        Ok(input) if strict => match normalize::check(puzzle.day, &input) {
+            Ok(()) => (puzzle.solve)(puzzle.day, &input, parts),
+            Err(e) => runner::failed(puzzle.day, parts, Duration::ZERO, e.render()),
+        },
+        Ok(input) => (puzzle.solve)(puzzle.day, &input, parts),
+        Err(e) => runner::failed(puzzle.day, parts, Duration::ZERO, e.to_string()),
+    }
+}
+
+fn day01_lexicon(path: Option<&Path>, ignore_case: bool) -> Result<Option<Lexicon>, String> {
+    let lexicon = match path {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|e| format!("can't read {}: {}", path.display(), e))?
+            .parse::<Lexicon>()
+            .map_err(|e| format!("invalid lexicon {}: {}", path.display(), e.render()))?,
+        None if ignore_case => Lexicon::english(),
+        None => return Ok(None),
+    };
+    if ignore_case {
+        lexicon.ignore_case().map(Some).map_err(|e| e.to_string())
+    } else {
+        Ok(Some(lexicon))
+    }
+}
+
+fn load_and_solve_day01(lexicon: Option<&Lexicon>, args: &RunArgs) -> DayReport {
+    let input = match input::load(1, &args.input) {
+        Ok(input) => input,
+        Err(e) => return runner::failed(1, &args.parts, Duration::ZERO, e.to_string()),
+    };
+    if args.strict {
+        if let Err(e) = normalize::check(1, &input) {
+            return runner::failed(1, &args.parts, Duration::ZERO, e.render());
+        }
+    }
+    let digits = Lexicon::digits();
+    let english = Lexicon::english();
+    runner::solve_with(
+        1,
+        &input,
+        &args.parts,
+        str::parse::<Document>,
+        |document, part, notes| {
+            let lexicon = match part {
+                1 => &digits,
+                _ => lexicon.unwrap_or(&english),
+            };
+            let calibration =
+                day01::calibrate(document, lexicon, Extraction::PUZZLE, args.missing_digits)?;
+            notes.extend(calibration.summary());
+            Ok(Answer::from(calibration.sum))
+        },
+    )
+}
+
+fn run_parallel(
+    puzzles: &[&Puzzle],
+    solve: impl Fn(&Puzzle) -> DayReport + Sync,
+    args: &RunArgs,
+    answers: &Answers,
+) -> Vec<DayReport> {
+    let start = Instant::now();
+    let reports = puzzles
+        .par_iter()
+        .map(|puzzle| solve(puzzle))
+        .collect::<Vec<_>>();
+    let wall_time = start.elapsed();
+
+    if args.format == Format::Text {
+        for report in &reports {
+            print_report(report, answers);
+        }
+    }
+
+    let cpu_time = reports.iter().map(DayReport::total_time).sum::<Duration>();
+    let summary = format!(
+        "wall-clock {:.2?}, summed solve time {:.2?} ({:.1}x, {} worker threads)",
+        wall_time,
+        cpu_time,
+        cpu_time.as_secs_f64() / wall_time.as_secs_f64(),
+        rayon::current_num_threads()
+    );
+    if args.format == Format::Text {
+        println!("{}", summary);
+    } else {
+        eprintln!("{}", summary);
+    }
+    reports
+}
+
+fn print_report(report: &DayReport, answers: &Answers) {
+    println!("-------- DAY {:02} --------", report.day);
+    if let Some(e) = &report.error {
+        eprintln!("FAILED: {}", e);
+        return;
+    }
+    for record in report::records(report, answers) {
+        match (record.status, record.answer, record.expected, record.error) {
+            (Status::Mismatch, Some(answer), Some(expected), _) => println!(
+                "{}",
+                red(&format!(
+                    "PART {}: {} (expected {})",
+                    record.part, answer, expected
+                ))
+            ),
+            (_, Some(answer), _, _) => println!("PART {}: {}", record.part, answer),
+            (_, _, _, error) => {
+                eprintln!("PART {} FAILED: {}", record.part, error.unwrap_or_default())
+            }
+        }
+        for note in &record.notes {
+            println!("  {}", note);
+        }
+    }
+}
+
+fn red(text: &str) -> String {
+    if io::stdout().is_terminal() {
+        format!("\x1b[31m{}\x1b[0m", text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn record_answers(
+    reports: &[DayReport],
+    answers: &mut Answers,
+    path: &std::path::Path,
+    overwrite: bool,
+) -> io::Result<()> {
+    let mut recorded = 0;
+    for report in reports {
+        let Some(input_id) = report.input_id else {
+            continue;
+        };
+        for part in &report.parts {
+            let Ok(answer) = &part.answer else {
+                continue;
+            };
+            let day = report.day;
+            let stored = if overwrite {
+                Ok(answers.overwrite(day, part.part, input_id, answer.to_string()))
+            } else {
+                answers.record(day, part.part, input_id, answer.to_string())
+            };
+            match stored {
+                Ok(true) => recorded += 1,
+                Ok(false) => {}
+                Err(verified) => eprintln!(
+                    "day {:02} part {}: keeping the verified answer {} instead of {}, \
+                     pass --overwrite to replace it",
+                    day, part.part, verified, answer
+                ),
+            }
+        }
+    }
+    if recorded > 0 {
+        answers.save(path)?;
+    }
+    eprintln!("recorded {} new answers in {}", recorded, path.display());
+    Ok(())
+}
+
+fn run_benches(puzzles: &[&Puzzle], args: &RunArgs, options: &BenchOptions) -> bool {
+    let mut succeeded = true;
+    let mut benches = vec![];
+    for puzzle in puzzles {
+        let day = puzzle.day;
+        let input = match input::load(day, &args.input) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                succeeded = false;
+                continue;
+            }
+        };
+        if args.strict {
+            if let Err(e) = normalize::check(day, &input) {
+                eprintln!("DAY {:02} FAILED: {}", day, e.render());
+                succeeded = false;
+                continue;
+            }
+        }
+        match panic::catch_unwind(|| (puzzle.bench)(day, &input, &args.parts, options)) {
+            Ok(Ok(bench)) => benches.push(bench),
+            Ok(Err(e)) => {
+                eprintln!("DAY {:02} FAILED: {}", day, e.render());
+                succeeded = false;
+            }
+            Err(payload) => {
+                eprintln!("DAY {:02} FAILED: {}", day, runner::panic_message(payload));
+                succeeded = false;
+            }
+        }
+    }
+    print!("{}", bench::render_table(&benches));
+    succeeded
 }
@@ -0,0 +1,269 @@
+use crate::differential;
+use crate::registry::Puzzle;
+use crate::runner;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Predicate {
+    Panic,
+    Error,
+    Disagreement,
+}
+
+impl FromStr for Predicate {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "panic" => Ok(Predicate::Panic),
+            "error" => Ok(Predicate::Error),
+            "disagreement" => Ok(Predicate::Disagreement),
+            _ => Err(format!(
+                "unknown predicate {}, expected panic, error or disagreement",
+                raw
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Predicate::Panic => write!(f, "panic"),
+            Predicate::Error => write!(f, "fail with an error"),
+            Predicate::Disagreement => write!(f, "make the solution and the reference disagree"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reduction {
+    Lines,
+    Blocks,
+    Columns,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MinimizeError {
+    NoReference(u8),
+    NotFailing(Predicate),
+}
+
+impl fmt::Display for MinimizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinimizeError::NoReference(day) => {
+                write!(f, "day {:02} has no reference solver to disagree with", day)
+            }
+            MinimizeError::NotFailing(predicate) => {
+                write!(f, "the input doesn't {}, nothing to minimize", predicate)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MinimizeError {}
+
+pub fn output_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("minimized-day{:02}.txt", day))
+}
+
+pub fn minimize(
+    puzzle: &Puzzle,
+    predicate: Predicate,
+    input: &str,
+) -> Result<String, MinimizeError> {
+    if predicate == Predicate::Disagreement && puzzle.reference.is_none() {
+        return Err(MinimizeError::NoReference(puzzle.day));
+    }
+    let expected = failure(puzzle, predicate, input).ok_or(MinimizeError::NotFailing(predicate))?;
+    let fails = |candidate: &str| {
+        !candidate.trim().is_empty()
+            && failure(puzzle, predicate, candidate).as_ref() == Some(&expected)
+    };
+
+    let mut smallest = input.to_string();
+    loop {
+        let before = smallest.clone();
+        for &reduction in puzzle.reductions {
+            smallest = reduce(&smallest, reduction, &fails);
+        }
+        if smallest == before {
+            return Ok(smallest);
+        }
+    }
+}
+
+fn failure(puzzle: &Puzzle, predicate: Predicate, input: &str) -> Option<String> {
+    if predicate == Predicate::Disagreement {
+        let reference = puzzle.reference?;
+        return differential::difference(puzzle, reference, input, &[1, 2])
+            .map(|difference| format!("part {}", difference.part));
+    }
+    let report = (puzzle.solve)(puzzle.day, input, &[1, 2]);
+    report
+        .parts
+        .into_iter()
+        .filter_map(|part| part.answer.err())
+        .find_map(|message| match predicate {
+            Predicate::Panic => runner::is_panic(&message).then(|| "panic".to_string()),
+            _ if runner::is_panic(&message) => None,
+            _ => Some(
+                message
+                    .lines()
+                    .next()
+                    .and_then(|line| line.split_once(": expected "))
+                    .map_or(message.clone(), |(_, expected)| expected.to_string()),
+            ),
+        })
+}
+
+fn reduce(input: &str, reduction: Reduction, fails: &dyn Fn(&str) -> bool) -> String {
+    match reduction {
+        Reduction::Lines => {
+            let lines = input.lines().collect::<Vec<_>>();
+            let join = |lines: &[&str]| -> String {
+                lines.iter().map(|line| format!("{}\n", line)).collect()
+            };
+            join(&ddmin(lines, |lines| fails(&join(lines))))
+        }
+        Reduction::Blocks => {
+            let blocks = input.trim_end().split("\n\n").collect::<Vec<_>>();
+            let join = |blocks: &[&str]| format!("{}\n", blocks.join("\n\n"));
+            join(&ddmin(blocks, |blocks| fails(&join(blocks))))
+        }
+        Reduction::Columns => {
+            let rows = input
+                .lines()
+                .map(|row| row.chars().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+            let join = |columns: &[usize]| {
+                rows.iter()
+                    .map(|row| {
+                        let mut line = columns
+                            .iter()
+                            .filter_map(|&column| row.get(column))
+                            .collect::<String>();
+                        line.push('\n');
+                        line
+                    })
+                    .collect::<String>()
+            };
+            join(&ddmin((0..width).collect(), |columns| {
+                fails(&join(columns))
+            }))
+        }
+    }
+}
+
+fn ddmin<T: Copy>(mut units: Vec<T>, fails: impl Fn(&[T]) -> bool) -> Vec<T> {
+    let mut granularity = 2;
+    while units.len() >= 2 {
+        let chunk = units.len().div_ceil(granularity);
+        let reduced = (0..units.len()).step_by(chunk).find_map(|start| {
+            let end = (start + chunk).min(units.len());
+            let complement = [&units[..start], &units[end..]].concat();
+            fails(&complement).then_some(complement)
+        });
+        match reduced {
+            Some(complement) => {
+                units = complement;
+                granularity = (granularity - 1).max(2);
+            }
+            None if granularity >= units.len() => break,
+            None => granularity = (granularity * 2).min(units.len()),
+        }
+    }
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::{Answer, Solution};
+
+    struct Fragile;
+
+    impl Solution for Fragile {
+        type Input = String;
+
+        fn parse(input: &str) -> Result<String, ParseError> {
+            Ok(input.to_string())
+        }
+
+        fn part1(input: &String) -> Result<Answer, ParseError> {
+            if input.contains("boom") && input.contains('#') {
+                panic!("boom");
+            }
+            Ok(Answer::from(input.lines().count()))
+        }
+
+        fn part2(input: &String) -> Result<Answer, ParseError> {
+            Ok(Answer::from(input.matches('#').count()))
+        }
+    }
+
+    fn reference(input: &str, part: u8) -> Result<Answer, ParseError> {
+        let hashes = input.matches('#').count();
+        match part {
+            1 => Ok(Answer::from(input.lines().count())),
+            _ => Ok(Answer::from(hashes.min(2))),
+        }
+    }
+
+    #[test]
+    fn ddmin_finds_a_minimal_subset() {
+        let units = (0..100).collect::<Vec<_>>();
+        let minimal = ddmin(units, |units| units.contains(&17) && units.contains(&83));
+        assert_eq!(minimal, vec![17, 83]);
+    }
+
+    #[test]
+    fn minimizes_lines_to_the_culprit() {
+        let day01 = crate::registry::find(1).unwrap();
+        let input = "1abc2\npqr3stu8vwx\nnothing here\na1b2c3d4e5f\n";
+        assert_eq!(
+            minimize(day01, Predicate::Error, input),
+            Ok("nothing here\n".to_string())
+        );
+        assert_eq!(
+            minimize(day01, Predicate::Panic, input),
+            Err(MinimizeError::NotFailing(Predicate::Panic))
+        );
+    }
+
+    #[test]
+    fn minimizes_grid_rows_and_columns() {
+        let puzzle = Puzzle::new::<Fragile>(1, "Fragile")
+            .with_reductions(&[Reduction::Lines, Reduction::Columns]);
+        let input = "..........\n...boom...\n..........\n......#...\n";
+        assert_eq!(
+            minimize(&puzzle, Predicate::Panic, input),
+            Ok("boom\n...#\n".to_string())
+        );
+    }
+
+    #[test]
+    fn minimizes_blocks_to_a_disagreement() {
+        let puzzle = Puzzle::new::<Fragile>(1, "Fragile")
+            .with_reference(reference)
+            .with_reductions(&[Reduction::Blocks, Reduction::Lines]);
+        let input = "a\n#\n\nb\nc\n\n#\nd\n\n#\n";
+        assert_eq!(
+            minimize(&puzzle, Predicate::Disagreement, input),
+            Ok("#\n#\n#\n".to_string())
+        );
+        assert_eq!(
+            minimize(
+                &Puzzle::new::<Fragile>(1, "Fragile"),
+                Predicate::Disagreement,
+                input
+            ),
+            Err(MinimizeError::NoReference(1))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::error::{ParseError, Unexpected};
+use std::borrow::Cow;
+
+const BOM: char = '\u{feff}';
+
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    if anomaly(input).is_none() {
+        return Cow::Borrowed(input);
+    }
+    let mut normalized = String::with_capacity(input.len());
+    for line in input.strip_prefix(BOM).unwrap_or(input).lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    let content = normalized.trim_end_matches('\n').len();
+    if content < normalized.len() {
+        normalized.truncate(content + 1);
+    }
+    if content == 0 {
+        normalized.clear();
+    }
+    Cow::Owned(normalized)
+}
+
+pub fn check(day: u8, input: &str) -> Result<(), ParseError> {
+    match anomaly(input) {
+        Some(unexpected) => Err(unexpected.locate(day, input)),
+        None => Ok(()),
+    }
+}
+
+fn anomaly(input: &str) -> Option<Unexpected<'_>> {
+    if input.starts_with(BOM) {
+        return Some(Unexpected::new(input, "no byte order mark"));
+    }
+    for line in input.split_inclusive('\n') {
+        let content = line.strip_suffix('\n').unwrap_or(line);
+        if let Some(stripped) = content.strip_suffix('\r') {
+            return Some(Unexpected::end_of(stripped, "'\\n' line endings"));
+        }
+        let trimmed = content.trim_end();
+        if trimmed.len() < content.len() {
+            return Some(Unexpected::end_of(trimmed, "no trailing whitespace"));
+        }
+    }
+    let content = input.trim_end_matches('\n');
+    if input.len() > content.len() + 1 || (content.is_empty() && !input.is_empty()) {
+        return Some(Unexpected::end_of(content, "no blank lines at the end"));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_anomalies() {
+        assert_eq!(normalize("1abc2\n"), Cow::Borrowed("1abc2\n"));
+        assert_eq!(normalize("1abc2"), Cow::Borrowed("1abc2"));
+        assert_eq!(
+            normalize("\u{feff}Time: 7 15\r\nDistance: 9 40  \r\n\r\n\n"),
+            "Time: 7 15\nDistance: 9 40\n"
+        );
+        assert_eq!(normalize("seeds: 1\n \nmap:\t\n"), "seeds: 1\n\nmap:\n");
+        assert_eq!(normalize("\n\n"), "");
+    }
+
+    #[test]
+    fn strict_mode_reports_anomalies() {
+        let located = |input: &str| {
+            check(1, input)
+                .map_err(|e| (e.line, e.column, e.expected))
+                .err()
+        };
+        assert_eq!(located("1abc2\npqr3stu8vwx\n"), None);
+        assert_eq!(
+            located("\u{feff}1abc2\n"),
+            Some((1, 1, "no byte order mark".to_string()))
+        );
+        assert_eq!(
+            located("1abc2\npqr3stu8vwx\r\n"),
+            Some((2, 12, "'\\n' line endings".to_string()))
+        );
+        assert_eq!(
+            located("1abc2 \n"),
+            Some((1, 6, "no trailing whitespace".to_string()))
+        );
+        assert_eq!(
+            located("1abc2\n\n"),
+            Some((1, 6, "no blank lines at the end".to_string()))
+        );
+    }
+}
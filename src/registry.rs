@@ -0,0 +1,106 @@
+use crate::bench::{self, BenchOptions, DayBench};
+use crate::error::ParseError;
+use crate::generate::Generator;
+use crate::minimize::Reduction;
+use crate::runner::{self, DayReport};
+use crate::{Answer, Solution};
+
+pub type Reference = fn(&str, u8) -> Result<Answer, ParseError>;
+
+pub struct Puzzle {
+    pub day: u8,
+    pub title: &'static str,
+    pub solve: fn(u8, &str, &[u8]) -> DayReport,
+    pub bench: fn(u8, &str, &[u8], &BenchOptions) -> Result<DayBench, ParseError>,
+    pub generate: Option<Generator>,
+    pub reference: Option<Reference>,
+    pub reductions: &'static [Reduction],
+}
+
+impl Puzzle {
+    pub const fn new<S: Solution>(day: u8, title: &'static str) -> Puzzle {
+        Puzzle {
+            day,
+            title,
+            solve: runner::solve::<S>,
+            bench: bench::bench::<S>,
+            generate: None,
+            reference: None,
+            reductions: &[Reduction::Lines],
+        }
+    }
+
+    pub const fn with_generator(self, generate: Generator) -> Puzzle {
+        Puzzle {
+            generate: Some(generate),
+            ..self
+        }
+    }
+
+    pub const fn with_reference(self, reference: Reference) -> Puzzle {
+        Puzzle {
+            reference: Some(reference),
+            ..self
+        }
+    }
+
+    pub const fn with_reductions(self, reductions: &'static [Reduction]) -> Puzzle {
+        Puzzle { reductions, ..self }
+    }
+}
+
+pub const PUZZLES: &[Puzzle] = include!(concat!(env!("OUT_DIR"), "/puzzles.rs"));
+
+pub fn find(day: u8) -> Option<&'static Puzzle> {
+    PUZZLES.iter().find(|puzzle| puzzle.day == day)
+}
+
+pub fn days() -> Vec<u8> {
+    PUZZLES.iter().map(|puzzle| puzzle.day).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers, input, report};
+
+    #[test]
+    fn puzzles_are_ordered_and_unique() {
+        let days = days();
+        assert!(!days.is_empty());
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(PUZZLES.iter().all(|puzzle| !puzzle.title.is_empty()));
+    }
+
+    #[test]
+    fn find_by_day() {
+        assert_eq!(
+            find(5).map(|puzzle| puzzle.title),
+            Some("If You Give A Seed A Fertilizer")
+        );
+        assert!(find(25).is_none());
+    }
+
+    #[test]
+    fn registered_puzzles_match_recorded_answers() {
+        let answers = answers::Answers::load(&answers::answers_path()).unwrap();
+        for puzzle in PUZZLES {
+            let Ok(input) = input::load_day(puzzle.day) else {
+                continue;
+            };
+            let report = (puzzle.solve)(puzzle.day, &input, &[1, 2]);
+            for record in report::records(&report, &answers) {
+                if record.expected.is_none() {
+                    continue;
+                }
+                assert_eq!(
+                    record.status,
+                    report::Status::Ok,
+                    "day {} part {}",
+                    record.day,
+                    record.part
+                );
+            }
+        }
+    }
+}
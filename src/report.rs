@@ -0,0 +1,246 @@
+use crate::answers::Answers;
+use crate::runner::DayReport;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "unknown format {}, expected text, json or csv",
+                raw
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    Mismatch,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Error => write!(f, "error"),
+            Status::Mismatch => write!(f, "mismatch"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+    pub answer: Option<String>,
+    pub expected: Option<String>,
+    pub parse_time_ns: u128,
+    pub solve_time_ns: u128,
+    pub error: Option<String>,
+    pub notes: Vec<String>,
+}
+
+pub fn records(report: &DayReport, answers: &Answers) -> Vec<Record> {
+    report
+        .parts
+        .iter()
+        .map(|part| {
+            let expected = report
+                .input_id
+                .and_then(|id| answers.expected(report.day, part.part, id))
+                .map(str::to_string);
+            let (status, answer, error) = match &part.answer {
+                Ok(answer) => {
+                    let answer = answer.to_string();
+                    let status = match &expected {
+                        Some(expected) if *expected != answer => Status::Mismatch,
+                        _ => Status::Ok,
+                    };
+                    (status, Some(answer), None)
+                }
+                Err(e) => (Status::Error, None, Some(e.clone())),
+            };
+            Record {
+                day: report.day,
+                part: part.part,
+                status,
+                answer,
+                expected,
+                parse_time_ns: report.parse_time.as_nanos(),
+                solve_time_ns: part.solve_time.as_nanos(),
+                error,
+                notes: part.notes.clone(),
+            }
+        })
+        .collect()
+}
+
+pub fn render_json(records: &[Record]) -> String {
+    let mut json = String::from("[");
+    for (i, record) in records.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            "\n  {{\"day\": {}, \"part\": {}, \"status\": \"{}\", \"answer\": {}, \"expected\": {}, \"parse_time_ns\": {}, \"solve_time_ns\": {}, \"error\": {}, \"notes\": [{}]}}",
+            record.day,
+            record.part,
+            record.status,
+            json_optional_string(record.answer.as_deref()),
+            json_optional_string(record.expected.as_deref()),
+            record.parse_time_ns,
+            record.solve_time_ns,
+            json_optional_string(record.error.as_deref()),
+            record.notes.iter().map(|note| json_string(note)).collect::<Vec<_>>().join(", "),
+        )
+        .unwrap();
+    }
+    json.push_str(if records.is_empty() { "]\n" } else { "\n]\n" });
+    json
+}
+
+pub fn render_csv(records: &[Record]) -> String {
+    let mut csv =
+        String::from("day,part,status,answer,expected,parse_time_ns,solve_time_ns,error,notes\n");
+    for record in records {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            record.status,
+            csv_field(record.answer.as_deref().unwrap_or("")),
+            csv_field(record.expected.as_deref().unwrap_or("")),
+            record.parse_time_ns,
+            record.solve_time_ns,
+            csv_field(record.error.as_deref().unwrap_or("")),
+            csv_field(&record.notes.join("; ")),
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn json_optional_string(value: Option<&str>) -> String {
+    match value {
+        Some(value) => json_string(value),
+        None => "null".to_string(),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 6,
+                part: 1,
+                status: Status::Mismatch,
+                answer: Some("288".to_string()),
+                expected: Some("289".to_string()),
+                parse_time_ns: 1500,
+                solve_time_ns: 2000,
+                error: None,
+                notes: vec!["counted 2 lines without digits as zero: 3, 7".to_string()],
+            },
+            Record {
+                day: 6,
+                part: 2,
+                status: Status::Error,
+                answer: None,
+                expected: None,
+                parse_time_ns: 1500,
+                solve_time_ns: 0,
+                error: Some("expected \"Time:\"\n  |, here".to_string()),
+                notes: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn mismatching_answers() {
+        let report = crate::runner::solve::<crate::day06::Day06>(
+            6,
+            "Time: 7 15 30\nDistance: 9 40 200\n",
+            &[1, 2],
+        );
+        let mut answers = Answers::default();
+        answers.overwrite(6, 1, report.input_id.unwrap(), "288".to_string());
+        answers.overwrite(6, 2, report.input_id.unwrap(), "71502".to_string());
+        let statuses = records(&report, &answers)
+            .iter()
+            .map(|r| r.status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, vec![Status::Ok, Status::Mismatch]);
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            render_json(&sample_records()),
+            r#"[
+  {"day": 6, "part": 1, "status": "mismatch", "answer": "288", "expected": "289", "parse_time_ns": 1500, "solve_time_ns": 2000, "error": null, "notes": ["counted 2 lines without digits as zero: 3, 7"]},
+  {"day": 6, "part": 2, "status": "error", "answer": null, "expected": null, "parse_time_ns": 1500, "solve_time_ns": 0, "error": "expected \"Time:\"\n  |, here", "notes": []}
+]
+"#
+        );
+        assert_eq!(render_json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn csv_output() {
+        assert_eq!(
+            render_csv(&sample_records()),
+            "day,part,status,answer,expected,parse_time_ns,solve_time_ns,error,notes
+6,1,mismatch,288,289,1500,2000,,\"counted 2 lines without digits as zero: 3, 7\"
+6,2,error,,,1500,0,\"expected \"\"Time:\"\"
+  |, here\",
+"
+        );
+    }
+}
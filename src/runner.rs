@@ -0,0 +1,169 @@
+use crate::answers;
+use crate::error::ParseError;
+use crate::normalize;
+use crate::{Answer, Solution};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+const PANIC_PREFIX: &str = "panicked: ";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayReport {
+    pub day: u8,
+    pub input_id: Option<u64>,
+    pub parse_time: Duration,
+    pub error: Option<String>,
+    pub parts: Vec<PartReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Result<Answer, String>,
+    pub solve_time: Duration,
+    pub notes: Vec<String>,
+}
+
+impl DayReport {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && self.parts.iter().all(|p| p.answer.is_ok())
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.parts.iter().map(|p| p.solve_time).sum::<Duration>()
+    }
+}
+
+pub fn solve<S: Solution>(day: u8, input: &str, parts: &[u8]) -> DayReport {
+    solve_with(day, input, parts, S::parse, |parsed, part, _| match part {
+        1 => S::part1(parsed),
+        _ => S::part2(parsed),
+    })
+}
+
+pub fn solve_with<I>(
+    day: u8,
+    input: &str,
+    parts: &[u8],
+    parse: impl Fn(&str) -> Result<I, ParseError>,
+    solve: impl Fn(&I, u8, &mut Vec<String>) -> Result<Answer, ParseError>,
+) -> DayReport {
+    let input = normalize::normalize(input);
+    let input_id = Some(answers::input_id(&input));
+    let start = Instant::now();
+    let parsed = panic::catch_unwind(AssertUnwindSafe(|| parse(&input)));
+    let parse_time = start.elapsed();
+
+    let parsed = match parsed {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(e)) => {
+            return DayReport {
+                input_id,
+                ..failed(day, parts, parse_time, e.render())
+            }
+        }
+        Err(payload) => {
+            return DayReport {
+                input_id,
+                ..failed(day, parts, parse_time, panic_message(payload))
+            }
+        }
+    };
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let mut notes = vec![];
+            let start = Instant::now();
+            let answer = panic::catch_unwind(AssertUnwindSafe(|| solve(&parsed, part, &mut notes)));
+            let solve_time = start.elapsed();
+            PartReport {
+                part,
+                answer: match answer {
+                    Ok(Ok(answer)) => Ok(answer),
+                    Ok(Err(e)) => Err(e.render()),
+                    Err(payload) => Err(panic_message(payload)),
+                },
+                solve_time,
+                notes,
+            }
+        })
+        .collect();
+
+    DayReport {
+        day,
+        input_id,
+        parse_time,
+        error: None,
+        parts,
+    }
+}
+
+pub fn failed(day: u8, parts: &[u8], parse_time: Duration, error: String) -> DayReport {
+    DayReport {
+        day,
+        input_id: None,
+        parse_time,
+        parts: parts
+            .iter()
+            .map(|&part| PartReport {
+                part,
+                answer: Err(error.clone()),
+                solve_time: Duration::ZERO,
+                notes: vec![],
+            })
+            .collect(),
+        error: Some(error),
+    }
+}
+
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    };
+    format!("{}{}", PANIC_PREFIX, message)
+}
+
+pub fn is_panic(message: &str) -> bool {
+    message.starts_with(PANIC_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day06::Day06;
+
+    #[test]
+    fn solved_parts() {
+        let report = solve::<Day06>(6, "Time: 7 15 30\nDistance: 9 40 200\n", &[2, 1]);
+        let answers = report
+            .parts
+            .iter()
+            .map(|p| (p.part, p.answer.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            vec![(2, Ok(Answer::Number(71503))), (1, Ok(Answer::Number(288)))]
+        );
+        assert!(report.succeeded());
+    }
+
+    #[test]
+    fn failed_part() {
+        let report = solve::<Day06>(6, "Time: 7 15 30\n", &[1]);
+        assert!(!report.succeeded());
+        assert_eq!(
+            report.parts[0].answer,
+            Err("day 06, line 1, column 14: expected a distance line
+  |
+1 | Time: 7 15 30
+  |              ^"
+                .to_string())
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{examples, input};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    AlreadyExists(PathBuf),
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::AlreadyExists(path) => {
+                write!(
+                    f,
+                    "{} already exists, refusing to overwrite it",
+                    path.display()
+                )
+            }
+            ScaffoldError::Io { path, source } => {
+                write!(f, "can't write {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let module_path = root.join("src").join(format!("day{:02}.rs", day));
+    create_new(&module_path, &template(day, title))?;
+    let mut created = vec![module_path];
+
+    let example_dir = examples::day_dir(&root.join("examples"), day);
+    let stubs = [
+        input::input_path(&root.join(input::input_dir()), day),
+        example_dir.join("sample.txt"),
+        example_dir.join("sample.expected"),
+    ];
+    for path in stubs {
+        match create_new(&path, "") {
+            Ok(()) => created.push(path),
+            Err(ScaffoldError::AlreadyExists(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(created)
+}
+
+fn create_new(path: &Path, content: &str) -> Result<(), ScaffoldError> {
+    let io_error = |source| ScaffoldError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => ScaffoldError::AlreadyExists(path.to_path_buf()),
+            _ => io_error(e),
+        })?;
+    file.write_all(content.as_bytes()).map_err(io_error)
+}
+
+pub fn template(day: u8, title: &str) -> String {
+    TEMPLATE
+        .replace("{{day}}", &day.to_string())
+        .replace("{{day:02}}", &format!("{:02}", day))
+        .replace(
+            "{{title}}",
+            &title.replace('\\', "\\\\").replace('"', "\\\""),
+        )
+}
+
+const TEMPLATE: &str = include_str!("scaffold/template.rs.in");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn template_names_the_day() {
+        let module = template(7, "Camel \"Cards\"");
+        assert!(module.contains("const DAY: u8 = 7;"));
+        assert!(module.contains("pub struct Day07;"));
+        assert!(module.contains("Puzzle::new::<Day07>(DAY, \"Camel \\\"Cards\\\"\")"));
+        assert!(!module.contains("{{"));
+    }
+
+    #[test]
+    fn refuses_to_overwrite() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let (src, input, examples) = (
+            root.join("src"),
+            root.join(input::input_dir()),
+            root.join("examples"),
+        );
+
+        let created = new_day(&root, 9, "Mirage Maintenance").unwrap();
+        assert_eq!(
+            created,
+            vec![
+                src.join("day09.rs"),
+                input.join("day09.txt"),
+                examples.join("day09/sample.txt"),
+                examples.join("day09/sample.expected"),
+            ]
+        );
+        assert_eq!(fs::read_to_string(input.join("day09.txt")).unwrap(), "");
+
+        let error = new_day(&root, 9, "Mirage Maintenance").unwrap_err();
+        assert!(
+            matches!(error, ScaffoldError::AlreadyExists(path) if path == src.join("day09.rs"))
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,620 @@
+use crate::fetch::{USER_AGENT, YEAR};
+use crate::http::{self, HttpError};
+use std::fmt;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+pub const ATTEMPTS_FILE_VAR: &str = "AOC_ATTEMPTS_FILE";
+pub const DEFAULT_ATTEMPTS_FILE: &str = "attempts.txt";
+
+const HEADER: &str = "# day part timestamp verdict wait answer
+# Every answer submitted with `aoc submit`, with the reply and the seconds to wait afterwards.
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited,
+    WrongLevel,
+}
+
+impl Verdict {
+    fn name(self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::RateLimited => "rate-limited",
+            Verdict::WrongLevel => "wrong-level",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Verdict> {
+        [
+            Verdict::Correct,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wrong,
+            Verdict::RateLimited,
+            Verdict::WrongLevel,
+        ]
+        .into_iter()
+        .find(|verdict| verdict.name() == name)
+    }
+
+    fn is_wrong(self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().replace('-', " "))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub timestamp: u64,
+    pub verdict: Verdict,
+    pub wait: u64,
+    pub answer: String,
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Unreadable { path: PathBuf, source: io::Error },
+    Malformed { path: PathBuf, line: usize },
+    InvalidAnswer(String),
+    AlreadySolved { answer: String },
+    KnownWrong { verdict: Verdict },
+    OutOfBounds { verdict: Verdict, bound: u64 },
+    Wait { seconds: u64 },
+    Http(HttpError),
+    Status { status: u16, body: String },
+    UnrecognizedReply(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Unreadable { path, source } => {
+                write!(f, "can't read {}: {}", path.display(), source)
+            }
+            SubmitError::Malformed { path, line } => write!(
+                f,
+                "{}:{}: expected `day part timestamp verdict wait answer`",
+                path.display(),
+                line
+            ),
+            SubmitError::InvalidAnswer(answer) => {
+                write!(f, "{:?} is empty or contains whitespace", answer)
+            }
+            SubmitError::AlreadySolved { answer } => {
+                write!(f, "already solved, the accepted answer was {}", answer)
+            }
+            SubmitError::KnownWrong { verdict } => {
+                write!(f, "this answer was already rejected as {}", verdict)
+            }
+            SubmitError::OutOfBounds { verdict, bound } => {
+                write!(f, "{} was already rejected as {}", bound, verdict)
+            }
+            SubmitError::Wait { seconds } => {
+                write!(f, "rate limited, wait {}s before submitting again", seconds)
+            }
+            SubmitError::Http(e) => write!(f, "{}", e),
+            SubmitError::Status { status, body } => write!(
+                f,
+                "the server replied with status {}: {}",
+                status,
+                body.lines().next().unwrap_or("").trim()
+            ),
+            SubmitError::UnrecognizedReply(text) => write!(f, "unrecognized reply: {}", text),
+        }
+    }
+}
+
+impl SubmitError {
+    /// Whether the answer was refused before anything was sent.
+    pub fn refused(&self) -> bool {
+        matches!(
+            self,
+            SubmitError::InvalidAnswer(_)
+                | SubmitError::AlreadySolved { .. }
+                | SubmitError::KnownWrong { .. }
+                | SubmitError::OutOfBounds { .. }
+                | SubmitError::Wait { .. }
+        )
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Attempts {
+    entries: Vec<Attempt>,
+}
+
+impl Attempts {
+    pub fn load(path: &Path) -> Result<Attempts, SubmitError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Attempts::parse(&content).map_err(|line| SubmitError::Malformed {
+                path: path.to_path_buf(),
+                line,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Attempts::default()),
+            Err(source) => Err(SubmitError::Unreadable {
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Attempts, usize> {
+        let mut entries = vec![];
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.splitn(6, ' ').collect::<Vec<_>>();
+            let attempt = match fields[..] {
+                [day, part, timestamp, verdict, wait, answer] => Some(Attempt {
+                    day: day.parse().map_err(|_| number + 1)?,
+                    part: part.parse().map_err(|_| number + 1)?,
+                    timestamp: timestamp.parse().map_err(|_| number + 1)?,
+                    verdict: Verdict::from_name(verdict).ok_or(number + 1)?,
+                    wait: wait.parse().map_err(|_| number + 1)?,
+                    answer: answer.to_string(),
+                }),
+                _ => None,
+            };
+            entries.push(attempt.ok_or(number + 1)?);
+        }
+        Ok(Attempts { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = HEADER.to_string();
+        for attempt in &self.entries {
+            writeln!(
+                content,
+                "{:02} {} {} {} {} {}",
+                attempt.day,
+                attempt.part,
+                attempt.timestamp,
+                attempt.verdict.name(),
+                attempt.wait,
+                attempt.answer
+            )
+            .unwrap();
+        }
+        fs::write(path, content)
+    }
+
+    pub fn record(&mut self, attempt: Attempt) {
+        self.entries.push(attempt);
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &str, now: u64) -> Result<(), SubmitError> {
+        let wait_until = self
+            .entries
+            .iter()
+            .map(|attempt| attempt.timestamp + attempt.wait)
+            .max()
+            .unwrap_or(0);
+        if now < wait_until {
+            return Err(SubmitError::Wait {
+                seconds: wait_until - now,
+            });
+        }
+
+        let attempts = self
+            .entries
+            .iter()
+            .filter(|attempt| attempt.day == day && attempt.part == part)
+            .collect::<Vec<_>>();
+        if let Some(correct) = attempts.iter().find(|a| a.verdict == Verdict::Correct) {
+            return Err(SubmitError::AlreadySolved {
+                answer: correct.answer.clone(),
+            });
+        }
+        if let Some(wrong) = attempts
+            .iter()
+            .find(|a| a.verdict.is_wrong() && a.answer == answer)
+        {
+            return Err(SubmitError::KnownWrong {
+                verdict: wrong.verdict,
+            });
+        }
+
+        let Ok(value) = answer.parse::<u64>() else {
+            return Ok(());
+        };
+        let bound = |verdict| {
+            attempts
+                .iter()
+                .filter(move |a| a.verdict == verdict)
+                .filter_map(|a| a.answer.parse::<u64>().ok())
+        };
+        if let Some(high) = bound(Verdict::TooHigh).filter(|&high| value >= high).min() {
+            return Err(SubmitError::OutOfBounds {
+                verdict: Verdict::TooHigh,
+                bound: high,
+            });
+        }
+        if let Some(low) = bound(Verdict::TooLow).filter(|&low| value <= low).max() {
+            return Err(SubmitError::OutOfBounds {
+                verdict: Verdict::TooLow,
+                bound: low,
+            });
+        }
+        Ok(())
+    }
+}
+
+pub fn attempts_path() -> PathBuf {
+    env::var_os(ATTEMPTS_FILE_VAR)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ATTEMPTS_FILE))
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+pub fn submit(
+    attempts: &mut Attempts,
+    base_url: &str,
+    session: &str,
+    day: u8,
+    part: u8,
+    answer: &str,
+    now: u64,
+) -> Result<Attempt, SubmitError> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(SubmitError::InvalidAnswer(answer.to_string()));
+    }
+    attempts.check(day, part, answer, now)?;
+
+    let url = format!(
+        "{}/{}/day/{}/answer",
+        base_url.trim_end_matches('/'),
+        YEAR,
+        day
+    );
+    let cookie = format!("session={}", session);
+    let level = part.to_string();
+    let response = http::post_form(
+        &url,
+        &[("Cookie", &cookie), ("User-Agent", USER_AGENT)],
+        &[("level", &level), ("answer", answer)],
+    )
+    .map_err(SubmitError::Http)?;
+    if response.status != 200 {
+        return Err(SubmitError::Status {
+            status: response.status,
+            body: response.body,
+        });
+    }
+
+    let text = article_text(&response.body);
+    let (verdict, wait) = parse_reply(&text).ok_or(SubmitError::UnrecognizedReply(text))?;
+    let attempt = Attempt {
+        day,
+        part,
+        timestamp: now,
+        verdict,
+        wait,
+        answer: answer.to_string(),
+    };
+    attempts.record(attempt.clone());
+    Ok(attempt)
+}
+
+fn article_text(html: &str) -> String {
+    let (article, mut in_tag) = match html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+    {
+        Some((article, _)) => (article, true),
+        None => (html, false),
+    };
+    let mut text = String::new();
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_reply(text: &str) -> Option<(Verdict, u64)> {
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("gave an answer too recently") {
+        Verdict::RateLimited
+    } else if text.contains("solving the right level") {
+        Verdict::WrongLevel
+    } else if text.contains("That's not the right answer") {
+        if text.contains("answer is too high") {
+            Verdict::TooHigh
+        } else if text.contains("answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else {
+        return None;
+    };
+    Some((verdict, parse_wait(text)))
+}
+
+fn parse_wait(text: &str) -> u64 {
+    if let Some((before, _)) = text.split_once(" left to wait") {
+        let left = before
+            .rsplit_once("You have ")
+            .map_or(before, |(_, left)| left);
+        return left
+            .split_whitespace()
+            .filter_map(|amount| {
+                let (number, unit) = amount.split_at(amount.len().checked_sub(1)?);
+                let number = number.parse::<u64>().ok()?;
+                match unit {
+                    "h" => Some(number * 3600),
+                    "m" => Some(number * 60),
+                    "s" => Some(number),
+                    _ => None,
+                }
+            })
+            .sum();
+    }
+    let lower = text.to_lowercase();
+    let Some((_, rest)) = lower.split_once("please wait ") else {
+        return 0;
+    };
+    let mut words = rest.split_whitespace();
+    let amount = match words.next() {
+        Some("one") => 1,
+        Some(number) => number.parse::<u64>().unwrap_or(0),
+        None => 0,
+    };
+    match words.next() {
+        Some(unit) if unit.starts_with("minute") => amount * 60,
+        Some(unit) if unit.starts_with("second") => amount,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server;
+
+    const NOW: u64 = 1_701_820_800;
+
+    fn attempt(part: u8, verdict: Verdict, answer: &str) -> Attempt {
+        Attempt {
+            day: 5,
+            part,
+            timestamp: NOW - 3600,
+            verdict,
+            wait: 60,
+            answer: answer.to_string(),
+        }
+    }
+
+    fn reply(article: &str) -> String {
+        test_server::response(
+            200,
+            &format!(
+                "<html><main><article><p>{}</p></article></main></html>",
+                article
+            ),
+        )
+    }
+
+    #[test]
+    fn parse_and_save_attempts() {
+        let content = format!(
+            "{}05 1 {} too-high 60 1234\n05 2 {} correct 0 17\n",
+            HEADER,
+            NOW - 3600,
+            NOW
+        );
+        let attempts = Attempts::parse(&content).unwrap();
+        assert_eq!(
+            attempts.entries,
+            vec![
+                attempt(1, Verdict::TooHigh, "1234"),
+                Attempt {
+                    timestamp: NOW,
+                    wait: 0,
+                    ..attempt(2, Verdict::Correct, "17")
+                },
+            ]
+        );
+        assert_eq!(Attempts::parse("05 1 0 too-high 1234\n"), Err(1));
+        assert_eq!(Attempts::parse("\n05 1 0 maybe 0 1234\n"), Err(2));
+
+        let path = env::temp_dir().join(format!("aoc-attempts-{}", std::process::id()));
+        attempts.save(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn refuses_known_wrong_and_out_of_bounds() {
+        let attempts = Attempts {
+            entries: vec![
+                attempt(1, Verdict::TooHigh, "500"),
+                attempt(1, Verdict::TooLow, "100"),
+                attempt(1, Verdict::Wrong, "250"),
+                attempt(2, Verdict::Correct, "42"),
+            ],
+        };
+        assert!(attempts.check(5, 1, "300", NOW).is_ok());
+        assert!(attempts.check(5, 1, "x", NOW).is_ok());
+        assert!(matches!(
+            attempts.check(5, 1, "250", NOW),
+            Err(SubmitError::KnownWrong {
+                verdict: Verdict::Wrong
+            })
+        ));
+        assert!(matches!(
+            attempts.check(5, 1, "500", NOW),
+            Err(SubmitError::KnownWrong {
+                verdict: Verdict::TooHigh
+            })
+        ));
+        assert!(matches!(
+            attempts.check(5, 1, "501", NOW),
+            Err(SubmitError::OutOfBounds {
+                verdict: Verdict::TooHigh,
+                bound: 500
+            })
+        ));
+        assert!(matches!(
+            attempts.check(5, 1, "99", NOW),
+            Err(SubmitError::OutOfBounds {
+                verdict: Verdict::TooLow,
+                bound: 100
+            })
+        ));
+        assert!(matches!(
+            attempts.check(5, 2, "43", NOW),
+            Err(SubmitError::AlreadySolved { .. })
+        ));
+        assert!(attempts.check(6, 1, "501", NOW).is_ok());
+        assert!(attempts.check(5, 1, "99", NOW).unwrap_err().refused());
+        assert!(!SubmitError::UnrecognizedReply("?".to_string()).refused());
+    }
+
+    #[test]
+    fn waits_after_rate_limit() {
+        let attempts = Attempts {
+            entries: vec![Attempt {
+                timestamp: NOW - 10,
+                wait: 45,
+                ..attempt(1, Verdict::RateLimited, "300")
+            }],
+        };
+        assert!(matches!(
+            attempts.check(6, 1, "1", NOW),
+            Err(SubmitError::Wait { seconds: 35 })
+        ));
+        assert!(attempts.check(6, 1, "1", NOW + 35).is_ok());
+    }
+
+    #[test]
+    fn replies() {
+        assert_eq!(
+            parse_reply("That's the right answer! You are one gold star closer."),
+            Some((Verdict::Correct, 0))
+        );
+        assert_eq!(
+            parse_reply("That's not the right answer; your answer is too low. Please wait one minute before trying again."),
+            Some((Verdict::TooLow, 60))
+        );
+        assert_eq!(
+            parse_reply("That's not the right answer. Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again."),
+            Some((Verdict::Wrong, 300))
+        );
+        assert_eq!(
+            parse_reply("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 1m 23s left to wait."),
+            Some((Verdict::RateLimited, 83))
+        );
+        assert_eq!(
+            parse_reply(
+                "You don't seem to be solving the right level. Did you already complete it?"
+            ),
+            Some((Verdict::WrongLevel, 0))
+        );
+        assert_eq!(parse_reply("Something else"), None);
+    }
+
+    #[test]
+    fn submits_to_local_server() {
+        let server = test_server::serve(vec![
+            reply("That's not the right answer; your answer is too high. Please wait one minute before trying again."),
+            reply("<span>That's the right answer!</span> You are <em>one gold star</em> closer."),
+        ]);
+        let mut attempts = Attempts::default();
+
+        let first = submit(&mut attempts, &server.base_url, "s3cr3t", 5, 1, "900", NOW).unwrap();
+        assert_eq!((first.verdict, first.wait), (Verdict::TooHigh, 60));
+        let request = server.requests.recv().unwrap();
+        assert!(request.starts_with("POST /2023/day/5/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=s3cr3t\r\n"));
+        assert!(request.ends_with("level=1&answer=900"));
+
+        assert!(matches!(
+            submit(
+                &mut attempts,
+                &server.base_url,
+                "s3cr3t",
+                5,
+                1,
+                "800",
+                NOW + 30
+            ),
+            Err(SubmitError::Wait { seconds: 30 })
+        ));
+        assert!(matches!(
+            submit(
+                &mut attempts,
+                &server.base_url,
+                "s3cr3t",
+                5,
+                1,
+                "901",
+                NOW + 60
+            ),
+            Err(SubmitError::OutOfBounds { bound: 900, .. })
+        ));
+        assert!(matches!(
+            submit(
+                &mut attempts,
+                &server.base_url,
+                "s3cr3t",
+                5,
+                1,
+                "8\n00",
+                NOW + 60
+            ),
+            Err(SubmitError::InvalidAnswer(_))
+        ));
+        assert!(server.requests.try_recv().is_err());
+
+        let second = submit(
+            &mut attempts,
+            &server.base_url,
+            "s3cr3t",
+            5,
+            1,
+            " 800\n",
+            NOW + 60,
+        )
+        .unwrap();
+        assert_eq!(second.verdict, Verdict::Correct);
+        assert_eq!(second.answer, "800");
+        assert!(server.requests.recv().unwrap().ends_with("answer=800"));
+        assert_eq!(attempts.entries.len(), 2);
+    }
+}